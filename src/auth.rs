@@ -0,0 +1,172 @@
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{Error, HttpResponse};
+use std::collections::HashSet;
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+
+use crate::handlers::ApiResponse;
+
+/// Split a comma-separated `--api-token`/`--read-token` value (or its env
+/// var equivalent) into the set of tokens that should be accepted. Returns
+/// `None` when the input is absent or only whitespace/commas, so the guard
+/// it's passed to falls back to today's open behavior.
+pub fn parse_tokens(raw: Option<&str>) -> Option<HashSet<String>> {
+    let tokens: HashSet<String> = raw?
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if tokens.is_empty() {
+        None
+    } else {
+        Some(tokens)
+    }
+}
+
+/// Validates requests against a configured set of bearer tokens, modeled on
+/// rustypaste's token scheme. `label` identifies which policy rejected a
+/// request in logs (e.g. `"management"` or `"read"`). When `tokens` is
+/// `None`, every request passes through unchanged, so deployments that
+/// don't configure a token for this guard stay open.
+#[derive(Clone)]
+pub struct ApiTokenGuard {
+    tokens: Option<Rc<HashSet<String>>>,
+    label: &'static str,
+}
+
+impl ApiTokenGuard {
+    pub fn new(tokens: Option<HashSet<String>>, label: &'static str) -> Self {
+        Self {
+            tokens: tokens.map(Rc::new),
+            label,
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ApiTokenGuard
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = ApiTokenGuardMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ApiTokenGuardMiddleware {
+            service: Rc::new(service),
+            tokens: self.tokens.clone(),
+            label: self.label,
+        }))
+    }
+}
+
+pub struct ApiTokenGuardMiddleware<S> {
+    service: Rc<S>,
+    tokens: Option<Rc<HashSet<String>>>,
+    label: &'static str,
+}
+
+impl<S, B> Service<ServiceRequest> for ApiTokenGuardMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let expected = self.tokens.clone();
+        let label = self.label;
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let Some(expected) = expected else {
+                // No token configured for this guard: preserve today's open behavior.
+                return service.call(req).await.map(ServiceResponse::map_into_left_body);
+            };
+
+            let provided = extract_token(&req);
+            if provided.is_some_and(|token| expected.contains(&token)) {
+                return service.call(req).await.map(ServiceResponse::map_into_left_body);
+            }
+
+            log::warn!("Rejected {} request to {} with missing or invalid API token", label, req.path());
+            let response = HttpResponse::Unauthorized()
+                .json(ApiResponse::<()>::error("Missing or invalid API token"));
+            Ok(req.into_response(response).map_into_right_body())
+        })
+    }
+}
+
+/// Pull a bearer token out of `Authorization: Bearer`, `X-API-Token`, or a
+/// `?token=` query parameter, in that order.
+fn extract_token(req: &ServiceRequest) -> Option<String> {
+    if let Some(value) = req.headers().get("Authorization").and_then(|v| v.to_str().ok()) {
+        if let Some(token) = value.strip_prefix("Bearer ") {
+            return Some(token.to_string());
+        }
+    }
+
+    if let Some(token) = req.headers().get("X-API-Token").and_then(|v| v.to_str().ok()) {
+        return Some(token.to_string());
+    }
+
+    query_param(req.query_string(), "token").map(str::to_string)
+}
+
+/// Find the value of `key` in a raw (unparsed) query string, e.g.
+/// `query_param("token=abc&x=1", "token") == Some("abc")`. Tokens are
+/// expected to be plain bearer strings, so this deliberately skips
+/// percent-decoding rather than pulling in a form-encoding dependency.
+fn query_param<'a>(query: &'a str, key: &str) -> Option<&'a str> {
+    query.split('&').find_map(|pair| {
+        let (name, value) = pair.split_once('=')?;
+        (name == key).then_some(value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tokens_splits_comma_separated_values() {
+        let tokens = parse_tokens(Some("a, b ,c")).unwrap();
+        assert_eq!(tokens, HashSet::from(["a".to_string(), "b".to_string(), "c".to_string()]));
+    }
+
+    #[test]
+    fn parse_tokens_none_for_absent_input() {
+        assert!(parse_tokens(None).is_none());
+    }
+
+    #[test]
+    fn parse_tokens_none_for_blank_input() {
+        assert!(parse_tokens(Some(" , ,")).is_none());
+    }
+
+    #[test]
+    fn query_param_finds_matching_key() {
+        assert_eq!(query_param("token=abc&x=1", "token"), Some("abc"));
+    }
+
+    #[test]
+    fn query_param_none_when_key_absent() {
+        assert_eq!(query_param("x=1&y=2", "token"), None);
+    }
+
+    #[test]
+    fn query_param_none_for_empty_query_string() {
+        assert_eq!(query_param("", "token"), None);
+    }
+}
@@ -0,0 +1,152 @@
+use actix_cors::Cors;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::utils::MockError;
+
+/// CORS policy loaded from a config file via `--cors-config`, so the mock
+/// server can reproduce a real browser's preflight/credentialed behavior
+/// instead of always allowing everything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorsPolicy {
+    /// Allowed origins. Supports an exact origin, a `*` wildcard entry
+    /// (equivalent to `allow_any_origin`), or a regex when wrapped like
+    /// `"regex:^https://.*\\.example\\.com$"`.
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    #[serde(default)]
+    pub allowed_methods: Vec<String>,
+    #[serde(default)]
+    pub allowed_headers: Vec<String>,
+    #[serde(default)]
+    pub exposed_headers: Vec<String>,
+    #[serde(default)]
+    pub allow_credentials: bool,
+    #[serde(default)]
+    pub max_age: Option<usize>,
+}
+
+impl CorsPolicy {
+    pub fn load(path: &str) -> Result<Self, MockError> {
+        let raw = fs::read_to_string(path)
+            .map_err(|e| MockError::IoError(format!("Failed to read CORS config {}: {}", path, e)))?;
+
+        serde_json::from_str(&raw)
+            .map_err(|e| MockError::ParseError(format!("Invalid CORS config {}: {}", path, e)))
+    }
+
+    /// Build the actix `Cors` middleware described by this policy.
+    pub fn build(&self) -> Cors {
+        let mut cors = Cors::default();
+
+        if self.allowed_origins.iter().any(|o| o == "*") {
+            cors = cors.allow_any_origin();
+        } else {
+            for origin in &self.allowed_origins {
+                cors = if let Some(pattern) = origin.strip_prefix("regex:") {
+                    match regex::Regex::new(pattern) {
+                        Ok(regex) => cors.allowed_origin_fn(move |origin, _req_head| {
+                            origin.to_str().map(|o| regex.is_match(o)).unwrap_or(false)
+                        }),
+                        Err(e) => {
+                            log::warn!("Invalid CORS origin regex '{}': {}", pattern, e);
+                            cors
+                        }
+                    }
+                } else {
+                    cors.allowed_origin(origin)
+                };
+            }
+        }
+
+        cors = if self.allowed_methods.is_empty() {
+            cors.allow_any_method()
+        } else {
+            cors.allowed_methods(self.allowed_methods.iter().map(String::as_str))
+        };
+
+        cors = if self.allowed_headers.is_empty() {
+            cors.allow_any_header()
+        } else {
+            cors.allowed_headers(self.allowed_headers.iter().map(String::as_str).collect::<Vec<_>>())
+        };
+
+        for header in &self.exposed_headers {
+            cors = cors.expose_headers([header.as_str()]);
+        }
+
+        if self.allow_credentials {
+            cors = cors.supports_credentials();
+        }
+
+        if let Some(max_age) = self.max_age {
+            cors = cors.max_age(max_age);
+        }
+
+        cors
+    }
+}
+
+/// Build the effective CORS middleware: the policy from `--cors-config`
+/// when one is configured and loads successfully, otherwise today's
+/// permissive allow-everything default.
+pub fn build_cors(config_path: Option<&str>) -> Cors {
+    match config_path {
+        Some(path) => match CorsPolicy::load(path) {
+            Ok(policy) => {
+                log::info!("Loaded CORS policy from {}", path);
+                policy.build()
+            }
+            Err(e) => {
+                log::error!("Failed to load CORS config {}: {}; falling back to permissive default", path, e);
+                permissive_default()
+            }
+        },
+        None => permissive_default(),
+    }
+}
+
+fn permissive_default() -> Cors {
+    Cors::default()
+        .allow_any_origin()
+        .allow_any_method()
+        .allow_any_header()
+        .max_age(3600)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn load_parses_a_valid_policy() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            file,
+            r#"{{"allowed_origins": ["https://example.com"], "allowed_methods": ["GET"], "allow_credentials": true}}"#
+        )
+        .unwrap();
+
+        let policy = CorsPolicy::load(file.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(policy.allowed_origins, vec!["https://example.com"]);
+        assert_eq!(policy.allowed_methods, vec!["GET"]);
+        assert!(policy.allow_credentials);
+    }
+
+    #[test]
+    fn load_missing_file_is_io_error() {
+        let error = CorsPolicy::load("/nonexistent/cors-config-for-test.json").unwrap_err();
+        assert!(matches!(error, MockError::IoError(_)));
+    }
+
+    #[test]
+    fn load_invalid_json_is_parse_error() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "not json").unwrap();
+
+        let error = CorsPolicy::load(file.path().to_str().unwrap()).unwrap_err();
+        assert!(matches!(error, MockError::ParseError(_)));
+    }
+}
@@ -4,17 +4,25 @@ use futures_util::TryStreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::path::Path;
-use std::sync::Arc;
 use std::collections::HashMap;
+use std::sync::Arc;
 
+use crate::jsonrpc;
+use crate::latency;
+use crate::matching;
+use crate::proxy::{self, ProxyConfig};
+use crate::store::Store;
+use tracing::Instrument;
 use crate::utils::{
-    read_mock_file, 
-    get_services_list, 
-    create_service_directory,
-    save_json_file,
-    delete_service_directory,
+    read_mock_body_via_store,
+    read_response_meta_via_store,
+    get_service_methods_via_store,
+    error_status,
+    resolve_error_template,
+    mime_from_filename,
     MockError,
-    ServiceRegistry,
+    ServiceConfig,
+    SharedRegistry,
     match_dynamic_route,
     process_dynamic_service
 };
@@ -50,62 +58,188 @@ impl<T> ApiResponse<T> {
     }
 }
 
-/// Handle mock requests for services
-pub async fn handle_mock_request(
-    path: web::Path<String>,
-    req: HttpRequest,
-) -> Result<HttpResponse> {
-    let service_name = path.into_inner();
-    let method = req.method().as_str();
+/// Build a response for a static mock file: start from the service's
+/// default headers (`headers.json`), layer the status and headers captured
+/// by record mode (`utils::write_response_meta_via_store`) on top since a per-file
+/// override takes precedence, and serve the body as raw bytes under the
+/// content type resolved by `read_mock_body_via_store` so mocks aren't
+/// limited to JSON (SOAP/XML, plain text, or arbitrary binary downloads all
+/// work).
+async fn respond_with_mock_content(
+    store: &dyn Store,
+    service_name: &str,
+    method: &str,
+    body: Vec<u8>,
+    content_type: &str,
+    default_headers: &HashMap<String, String>,
+) -> HttpResponse {
+    let (status, overrides, _) =
+        read_response_meta_via_store(store, service_name, method).await.unwrap_or((200, HashMap::new(), None));
 
-    log::info!("Mock request: {} {}", method, service_name);
+    let mut builder = HttpResponse::build(
+        actix_web::http::StatusCode::from_u16(status).unwrap_or(actix_web::http::StatusCode::OK),
+    );
+    builder.content_type(content_type);
+    for (name, value) in default_headers.iter().chain(overrides.iter()) {
+        builder.insert_header((name.as_str(), value.as_str()));
+    }
+    builder.body(body)
+}
 
-    match read_mock_file(&service_name, method) {
-        Ok(content) => {
-            log::info!("Serving mock response for {} {}", method, service_name);
-            Ok(HttpResponse::Ok()
-                .content_type("application/json")
-                .json(content))
-        }
-        Err(MockError::FileNotFound(msg)) => {
-            log::warn!("Mock file not found: {}", msg);
-            Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error(&msg)))
-        }
-        Err(MockError::ParseError(msg)) => {
-            log::error!("JSON parse error: {}", msg);
-            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&msg)))
+/// Extract the request's `Accept` header, if present and valid UTF-8, for
+/// error-response content negotiation.
+fn accept_header(req: &HttpRequest) -> Option<&str> {
+    req.headers().get(actix_web::http::header::ACCEPT).and_then(|v| v.to_str().ok())
+}
+
+/// An absent `Accept` header, or one that names `application/json` or
+/// `*/*`, gets a JSON error body; anything else falls back to plain text.
+fn wants_json(accept: Option<&str>) -> bool {
+    accept.map(|value| value.contains("json") || value.contains("*/*")).unwrap_or(true)
+}
+
+/// Build a negotiated error response from a bare status and message, for
+/// error paths that have no per-route error template to render (e.g. an
+/// injected fault, or a dynamic error whose route declares no template for
+/// its status).
+fn error_response(status: u16, message: &str, accept: Option<&str>) -> HttpResponse {
+    let status_code = actix_web::http::StatusCode::from_u16(status).unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR);
+    if wants_json(accept) {
+        HttpResponse::build(status_code).json(ApiResponse::<()>::error(message))
+    } else {
+        HttpResponse::build(status_code).content_type("text/plain; charset=utf-8").body(message.to_string())
+    }
+}
+
+/// Evaluate a method's configured request-matching rules (if any) against
+/// the incoming request, returning the first matching rule's response.
+/// Returns `None` when no rules are configured or none match, so the
+/// caller falls through to the method's default (file-based or templated)
+/// response — a rule list is a set of overrides layered on top of today's
+/// single-response behavior, not a replacement for it.
+fn match_rule_response(
+    rules: Option<&Vec<matching::MatchRule>>,
+    req: &HttpRequest,
+    body: &[u8],
+    params: &HashMap<String, String>,
+    default_headers: &HashMap<String, String>,
+) -> Option<HttpResponse> {
+    let rules = rules?;
+    let parsed_body: Option<Value> = serde_json::from_slice(body).ok();
+    let query = matching::parse_query_string(req.query_string());
+    let headers = matching::header_map(req);
+    let match_request = matching::MatchRequest { body: parsed_body.as_ref(), query: &query, headers: &headers, params };
+
+    let rule = matching::select_response(rules, &match_request)?;
+    let status = rule.status.unwrap_or(200);
+    let mut builder = HttpResponse::build(
+        actix_web::http::StatusCode::from_u16(status).unwrap_or(actix_web::http::StatusCode::OK),
+    );
+    builder.content_type("application/json");
+    for (name, value) in default_headers {
+        builder.insert_header((name.as_str(), value.as_str()));
+    }
+    Some(builder.json(&rule.response))
+}
+
+/// Build the response for an injected fault: a `Status` fault responds
+/// immediately with the configured code, while a `Timeout` fault first hangs
+/// for the configured duration — simulating a genuinely slow/hung upstream
+/// rather than a fast failure — before finally giving up with a `504`. Both
+/// go through `error_response`, so the body is the `ApiResponse` error
+/// envelope, or plain text when `Accept` doesn't ask for JSON.
+async fn fault_response(action: &latency::FaultAction, accept: Option<&str>) -> HttpResponse {
+    match action {
+        latency::FaultAction::Status { status } => error_response(*status, "Injected fault", accept),
+        latency::FaultAction::Timeout { hang_ms } => {
+            tokio::time::sleep(std::time::Duration::from_millis(*hang_ms)).await;
+            error_response(504, "Injected fault: upstream timed out", accept)
         }
-        Err(MockError::IoError(msg)) => {
-            log::error!("IO error: {}", msg);
-            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&msg)))
+    }
+}
+
+/// Build the response for a dynamic service error, preferring the service's
+/// own per-route error template (rendered through the same Handlebars and
+/// transformer pipeline as a success response) over the generic fallback.
+fn dynamic_error_response(
+    service_config: &ServiceConfig,
+    error: &MockError,
+    params: &HashMap<String, String>,
+    accept: Option<&str>,
+) -> HttpResponse {
+    let status = error_status(error);
+
+    match resolve_error_template(service_config, error, params) {
+        Some(body) => {
+            let status_code = actix_web::http::StatusCode::from_u16(status).unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR);
+            if wants_json(accept) {
+                HttpResponse::build(status_code).json(body)
+            } else {
+                let text = body.as_str().map(|s| s.to_string()).unwrap_or_else(|| body.to_string());
+                HttpResponse::build(status_code).content_type("text/plain; charset=utf-8").body(text)
+            }
         }
+        None => error_response(status, &error.to_string(), accept),
     }
 }
 
-/// List all available services
-pub async fn list_services() -> Result<HttpResponse> {
+/// Parse and dispatch a JSON-RPC 2.0 request body against a JSON-RPC
+/// service's configured methods. Notifications (and all-notification
+/// batches) produce no body, per spec, so they're served as `204`.
+fn handle_json_rpc_request(service_config: &crate::utils::ServiceConfig, body: &[u8]) -> HttpResponse {
+    let raw = match std::str::from_utf8(body) {
+        Ok(s) => s,
+        Err(_) => return HttpResponse::BadRequest().body("Invalid UTF-8 request body"),
+    };
+
+    let parsed = match jsonrpc::parse_request(raw) {
+        Ok(value) => value,
+        Err(error_response) => return HttpResponse::Ok().json(error_response),
+    };
+
+    match jsonrpc::handle_request(service_config, &parsed) {
+        Some(response) => HttpResponse::Ok().json(response),
+        None => HttpResponse::NoContent().finish(),
+    }
+}
+
+/// List all available services. Which services exist comes from the
+/// registry built by `discover_services` (always local disk); each one's
+/// available methods are resolved through the configured `Store`, so the
+/// listing reflects mock bodies actually reachable by the serving handlers
+/// even when `--s3-bucket` is set.
+pub async fn list_services(
+    registry: web::Data<SharedRegistry>,
+    store: web::Data<Arc<dyn Store>>,
+) -> Result<HttpResponse> {
     log::info!("Listing all services");
-    
-    match get_services_list() {
-        Ok(services) => {
-            Ok(HttpResponse::Ok().json(ApiResponse::success(services)))
-        }
-        Err(e) => {
-            log::error!("Error listing services: {}", e);
-            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&format!("Error listing services: {}", e))))
+
+    let service_names: Vec<String> = registry.read().unwrap().services.keys().cloned().collect();
+    let mut services = Vec::new();
+    for service_name in service_names {
+        match get_service_methods_via_store(store.get_ref().as_ref(), &service_name).await {
+            Ok(methods) => services.push(ServiceInfo { name: service_name, methods }),
+            Err(e) => {
+                log::error!("Error listing methods for service {}: {}", service_name, e);
+                return Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&format!("Error listing services: {}", e))));
+            }
         }
     }
+    services.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(services)))
 }
 
 /// Create a new service directory
 pub async fn create_service(
     path: web::Path<String>,
+    store: web::Data<Arc<dyn Store>>,
 ) -> Result<HttpResponse> {
     let service_name = path.into_inner();
-    
+
     log::info!("Creating service: {}", service_name);
 
-    match create_service_directory(&service_name) {
+    match store.create_namespace(&service_name).await {
         Ok(_) => {
             Ok(HttpResponse::Created().json(ApiResponse::success(format!("Service '{}' created successfully", service_name))))
         }
@@ -123,41 +257,67 @@ pub async fn create_service(
 pub async fn upload_mock_file(
     path: web::Path<(String, String)>,
     mut payload: Multipart,
+    store: web::Data<Arc<dyn Store>>,
 ) -> Result<HttpResponse> {
     let (service_name, method) = path.into_inner();
     let method = method.to_uppercase();
-    
+
     log::info!("Uploading mock file for {} {}", method, service_name);
 
     // Validate HTTP method
-    if !["GET", "POST", "PUT", "DELETE"].contains(&method.as_str()) {
-        return Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error("Invalid HTTP method. Must be GET, POST, PUT, or DELETE")));
+    if !crate::routing::MOCKABLE_METHODS.contains(&method.as_str()) {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(
+            "Invalid HTTP method. Must be GET, POST, PUT, DELETE, PATCH, HEAD, or OPTIONS",
+        )));
     }
 
     while let Some(mut field) = payload.try_next().await? {
         let content_disposition = field.content_disposition();
-        
-        if let Some(filename) = content_disposition.and_then(|cd| cd.get_filename()) {
-            if !filename.ends_with(".json") {
-                return Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error("Only JSON files are allowed")));
-            }
-        }
+        let filename = content_disposition.and_then(|cd| cd.get_filename()).map(|s| s.to_string());
+
+        // The field's own declared Content-Type (set by the client sending
+        // the multipart part) wins, unless it is the generic
+        // `application/octet-stream`; fall back to inferring it from the
+        // uploaded filename's extension, then to a generic binary default,
+        // mirroring rustypaste's mime handling for arbitrary uploads.
+        let content_type = field
+            .content_type()
+            .map(|mime| mime.essence_str().to_string())
+            .filter(|mime| mime != "application/octet-stream")
+            .or_else(|| filename.as_deref().map(mime_from_filename).map(|s| s.to_string()))
+            .unwrap_or_else(|| "application/octet-stream".to_string());
 
         let mut file_content = Vec::new();
         while let Some(chunk) = field.try_next().await? {
             file_content.extend_from_slice(&chunk);
         }
 
-        let json_str = String::from_utf8(file_content)
-            .map_err(|_| actix_web::error::ErrorBadRequest("Invalid UTF-8 content"))?;
+        // JSON bodies are re-serialized pretty-printed like every other
+        // JSON fixture in this repo; everything else is stored as-is.
+        let bytes = if content_type == "application/json" {
+            let json_str = String::from_utf8(file_content)
+                .map_err(|_| actix_web::error::ErrorBadRequest("Invalid UTF-8 content"))?;
+            let json_value: Value = serde_json::from_str(&json_str)
+                .map_err(|e| actix_web::error::ErrorBadRequest(format!("Invalid JSON: {}", e)))?;
+            serde_json::to_vec_pretty(&json_value)
+                .map_err(|e| actix_web::error::ErrorInternalServerError(format!("Failed to serialize JSON: {}", e)))?
+        } else {
+            file_content
+        };
 
-        // Validate JSON
-        let json_value: Value = serde_json::from_str(&json_str)
-            .map_err(|e| actix_web::error::ErrorBadRequest(format!("Invalid JSON: {}", e)))?;
+        let key = format!("{}-{}.{}", service_name, method, crate::utils::extension_for_content_type(&content_type));
 
-        match save_json_file(&service_name, &method, &json_value) {
+        match store.write(&service_name, &key, &bytes).await {
             Ok(_) => {
-                log::info!("Mock file uploaded successfully for {} {}", method, service_name);
+                let (status, headers, _) = read_response_meta_via_store(store.get_ref().as_ref(), &service_name, &method)
+                    .await
+                    .unwrap_or((200, HashMap::new(), None));
+                if let Err(e) =
+                    crate::utils::write_response_meta_via_store(store.get_ref().as_ref(), &service_name, &method, status, &headers, Some(&content_type)).await
+                {
+                    log::warn!("Failed to record content-type override for {} {}: {}", method, service_name, e);
+                }
+                log::info!("Mock file uploaded successfully for {} {} ({})", method, service_name, content_type);
                 return Ok(HttpResponse::Created().json(ApiResponse::success(format!("Mock file uploaded for {} {}", method, service_name))));
             }
             Err(e) => {
@@ -173,12 +333,13 @@ pub async fn upload_mock_file(
 /// Delete a service and all its mock files
 pub async fn delete_service(
     path: web::Path<String>,
+    store: web::Data<Arc<dyn Store>>,
 ) -> Result<HttpResponse> {
     let service_name = path.into_inner();
-    
+
     log::info!("Deleting service: {}", service_name);
 
-    match delete_service_directory(&service_name) {
+    match store.delete_namespace(&service_name).await {
         Ok(_) => {
             Ok(HttpResponse::Ok().json(ApiResponse::success(format!("Service '{}' deleted successfully", service_name))))
         }
@@ -201,46 +362,90 @@ pub async fn health_check() -> Result<HttpResponse> {
     })))
 }
 
+/// Expose recorded metrics in Prometheus text exposition format.
+pub async fn metrics_endpoint() -> Result<HttpResponse> {
+    match crate::metrics::render() {
+        Ok(body) => Ok(HttpResponse::Ok().content_type("text/plain; version=0.0.4").body(body)),
+        Err(e) => {
+            log::error!("Failed to render metrics: {}", e);
+            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&format!("Failed to render metrics: {}", e))))
+        }
+    }
+}
+
 /// Handle dynamic requests with path parameters
 pub async fn handle_dynamic_request(
     path: web::Path<String>,
     req: HttpRequest,
-    registry: web::Data<Arc<ServiceRegistry>>,
+    body: web::Bytes,
+    registry: web::Data<SharedRegistry>,
+    global_latency_ms: web::Data<Option<u64>>,
+    proxy_config: web::Data<Option<ProxyConfig>>,
+    store: web::Data<Arc<dyn Store>>,
 ) -> Result<HttpResponse> {
     let request_path = format!("/{}", path.into_inner());
     let method = req.method().as_str();
-    
+    let registry = registry.read().unwrap();
+
     log::debug!("Dynamic request: {} {}", method, request_path);
-    
+
     // Try to match against dynamic routes
     if let Some((service_name, params)) = match_dynamic_route(&registry, &request_path, method) {
         log::info!("Matched dynamic route: {} -> service: {}, params: {:?}", request_path, service_name, params);
-        
-        if let Some(service_config) = registry.services.get(&service_name) {
-            match process_dynamic_service(service_config, params, method) {
-                Ok(content) => {
-                    log::info!("Serving dynamic response for {} {}", method, request_path);
-                    Ok(HttpResponse::Ok()
-                        .content_type("application/json")
-                        .json(content))
+        let span = tracing::info_span!("mock_request", service = %service_name, route_kind = "dynamic");
+
+        async {
+            if let Some(service_config) = registry.services.get(&service_name) {
+                let method_latency = service_config.latency.get(&method.to_uppercase());
+                if let Some(delay_ms) = latency::resolve_delay_ms(
+                    latency::header_override_ms(&req),
+                    method_latency,
+                    **global_latency_ms,
+                ) {
+                    tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
                 }
-                Err(MockError::FileNotFound(msg)) => {
-                    log::warn!("Dynamic service error: {}", msg);
-                    Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error(&msg)))
+
+                let method_fault = service_config.faults.get(&method.to_uppercase());
+                if let Some(action) = latency::resolve_fault(method_fault) {
+                    log::warn!("Injecting fault for {} {}: {:?}", method, service_name, action);
+                    crate::metrics::record_mock_outcome(&service_name, method, "fault_injected");
+                    let accept = accept_header(&req);
+                    return Ok(fault_response(&action, accept).await);
                 }
-                Err(MockError::ParseError(msg)) => {
-                    log::error!("Dynamic service parse error: {}", msg);
-                    Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(&msg)))
+
+                let method_rules = service_config.rules.get(&method.to_uppercase());
+                if let Some(response) = match_rule_response(method_rules, &req, &body, &params, &service_config.default_headers) {
+                    log::info!("Matched request rule for {} {}", method, service_name);
+                    crate::metrics::record_mock_outcome(&service_name, method, "rule_match");
+                    return Ok(response);
                 }
-                Err(MockError::IoError(msg)) => {
-                    log::error!("Dynamic service IO error: {}", msg);
-                    Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&msg)))
+
+                let params_for_error = params.clone();
+                match process_dynamic_service(service_config, params, method) {
+                    Ok(content) => {
+                        log::info!("Serving dynamic response for {} {}", method, request_path);
+                        crate::metrics::record_mock_outcome(&service_name, method, "hit");
+                        let mut builder = HttpResponse::Ok();
+                        builder.content_type("application/json");
+                        for (name, value) in &service_config.default_headers {
+                            builder.insert_header((name.as_str(), value.as_str()));
+                        }
+                        Ok(builder.json(content))
+                    }
+                    Err(e) => {
+                        log::warn!("Dynamic service error for {} {}: {}", method, request_path, e);
+                        crate::metrics::record_mock_outcome(&service_name, method, crate::metrics::mock_error_label(&e));
+                        let accept = accept_header(&req);
+                        Ok(dynamic_error_response(service_config, &e, &params_for_error, accept))
+                    }
                 }
+            } else {
+                log::error!("Service configuration not found for: {}", service_name);
+                Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Service configuration error")))
             }
-        } else {
-            log::error!("Service configuration not found for: {}", service_name);
-            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Service configuration error")))
         }
+        .instrument(span)
+        .await
     } else {
         // Fallback to static service lookup for backward compatibility
         let path_parts: Vec<&str> = request_path.trim_start_matches('/').split('/').collect();
@@ -249,27 +454,117 @@ pub async fn handle_dynamic_request(
             // Try legacy static service
             let service_name = path_parts[0];
             log::debug!("Trying legacy static service: {}", service_name);
-            
-            match read_mock_file(service_name, method) {
-                Ok(content) => {
-                    log::info!("Serving legacy static response for {} {}", method, service_name);
-                    Ok(HttpResponse::Ok()
-                        .content_type("application/json")
-                        .json(content))
+
+            if let Some(service_config) = registry.services.get(service_name) {
+                if matches!(service_config.service_type, crate::utils::ServiceType::JsonRpc { .. }) {
+                    return Ok(handle_json_rpc_request(service_config, &body));
                 }
-                Err(_) => {
-                    // Not found
-                    Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error(&format!(
-                        "No mock service found for path: {} {}",
-                        method, request_path
-                    ))))
+            }
+
+            let span = tracing::info_span!("mock_request", service = %service_name, route_kind = "legacy_static");
+
+            async {
+                let method_latency = registry
+                    .services
+                    .get(service_name)
+                    .and_then(|config| config.latency.get(&method.to_uppercase()));
+                if let Some(delay_ms) = latency::resolve_delay_ms(
+                    latency::header_override_ms(&req),
+                    method_latency,
+                    **global_latency_ms,
+                ) {
+                    tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+                }
+
+                let method_fault = registry.services.get(service_name).and_then(|config| config.faults.get(&method.to_uppercase()));
+                if let Some(action) = latency::resolve_fault(method_fault) {
+                    log::warn!("Injecting fault for {} {}: {:?}", method, service_name, action);
+                    crate::metrics::record_mock_outcome(service_name, method, "fault_injected");
+                    let accept = accept_header(&req);
+                    return Ok(fault_response(&action, accept).await);
+                }
+
+                let method_rules = registry.services.get(service_name).and_then(|config| config.rules.get(&method.to_uppercase()));
+                let default_headers = registry.services.get(service_name).map(|config| &config.default_headers).cloned().unwrap_or_default();
+                if let Some(response) = match_rule_response(method_rules, &req, &body, &HashMap::new(), &default_headers) {
+                    log::info!("Matched request rule for {} {}", method, service_name);
+                    crate::metrics::record_mock_outcome(service_name, method, "rule_match");
+                    return Ok(response);
+                }
+
+                match read_mock_body_via_store(store.get_ref().as_ref(), service_name, method).await {
+                    Ok((body, content_type)) => {
+                        log::info!("Serving legacy static response for {} {}", method, service_name);
+                        crate::metrics::record_mock_outcome(service_name, method, "hit");
+                        let default_headers = registry
+                            .services
+                            .get(service_name)
+                            .map(|config| &config.default_headers)
+                            .cloned()
+                            .unwrap_or_default();
+                        Ok(respond_with_mock_content(store.get_ref().as_ref(), service_name, method, body, &content_type, &default_headers).await)
+                    }
+                    Err(e) => {
+                        crate::metrics::record_mock_outcome(service_name, method, crate::metrics::mock_error_label(&e));
+                        // Not found locally; try the upstream proxy before giving up.
+                        match try_proxy_fallback(&proxy_config, store.get_ref().as_ref(), &req, &request_path, &body, service_name).await {
+                            Some(response) => Ok(response),
+                            None => Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error(&format!(
+                                "No mock service found for path: {} {}",
+                                method, request_path
+                            )))),
+                        }
+                    }
                 }
             }
+            .instrument(span)
+            .await
         } else {
-            Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error(&format!(
-                "No route configured for path: {} {}",
-                method, request_path
-            ))))
+            let fallback_service_name = path_parts.first().copied().unwrap_or("root");
+            let span = tracing::info_span!("mock_request", service = %fallback_service_name, route_kind = "proxy_fallback");
+            async {
+                match try_proxy_fallback(&proxy_config, store.get_ref().as_ref(), &req, &request_path, &body, fallback_service_name).await {
+                    Some(response) => Ok(response),
+                    None => Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error(&format!(
+                        "No route configured for path: {} {}",
+                        method, request_path
+                    )))),
+                }
+            }
+            .instrument(span)
+            .await
+        }
+    }
+}
+
+/// When proxy/record mode is configured, forward an unmatched request to the
+/// upstream and return its response; otherwise return `None` so the caller
+/// falls back to its normal 404.
+async fn try_proxy_fallback(
+    proxy_config: &Option<ProxyConfig>,
+    store: &dyn Store,
+    req: &HttpRequest,
+    request_path: &str,
+    body: &[u8],
+    service_name: &str,
+) -> Option<HttpResponse> {
+    let config = proxy_config.as_ref()?;
+
+    match proxy::forward_and_record(config, store, req.method(), request_path, req.query_string(), body, service_name).await {
+        Ok(recorded) => {
+            let mut builder = HttpResponse::build(
+                actix_web::http::StatusCode::from_u16(recorded.status)
+                    .unwrap_or(actix_web::http::StatusCode::OK),
+            );
+            builder.content_type(recorded.content_type.as_str());
+            for (name, value) in &recorded.headers {
+                builder.insert_header((name.as_str(), value.as_str()));
+            }
+            Some(builder.body(recorded.body))
+        }
+        Err(e) => {
+            log::error!("Proxy fallback failed for {}: {}", request_path, e);
+            Some(HttpResponse::BadGateway().json(ApiResponse::<()>::error(&format!("Upstream proxy error: {}", e))))
         }
     }
 }
\ No newline at end of file
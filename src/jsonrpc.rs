@@ -0,0 +1,375 @@
+use rquickjs::{Context, Runtime};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::utils::{MockError, ServiceConfig, ServiceType};
+
+/// `template` + `transformer` pair for a single JSON-RPC method, loaded from
+/// a service's `rpc.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcMethodConfig {
+    pub template: Value,
+    pub transformer: String,
+    /// Param names that must be present (and non-null) in `params` for a
+    /// call to this method, checked before the transformer runs. A missing
+    /// one fails with -32602 rather than reaching the transformer at all.
+    #[serde(default)]
+    pub required_params: Vec<String>,
+}
+
+/// Top-level shape of `rpc.json`: a map of JSON-RPC method name to its
+/// template/transformer pair.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcServiceConfig {
+    pub methods: HashMap<String, RpcMethodConfig>,
+}
+
+// Standard JSON-RPC 2.0 error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// Map a transformer-execution failure to the JSON-RPC error code it should
+/// be reported under: a `ValidationError` is a genuine client-side params
+/// problem (-32602), while everything else (a JS runtime crash, a
+/// transformer that threw, malformed JSON returned from it) is a
+/// server-side execution failure and belongs under -32603, not folded into
+/// "invalid params" alongside requests the caller actually got wrong.
+fn rpc_error_code(error: &MockError) -> i64 {
+    match error {
+        MockError::ValidationError(_) => INVALID_PARAMS,
+        _ => INTERNAL_ERROR,
+    }
+}
+
+fn rpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "error": { "code": code, "message": message },
+        "id": id,
+    })
+}
+
+fn rpc_result(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "result": result, "id": id })
+}
+
+/// Parse a raw request body as JSON-RPC, returning a standard -32700 parse
+/// error response when it isn't valid JSON at all.
+pub fn parse_request(raw_body: &str) -> Result<Value, Value> {
+    serde_json::from_str(raw_body).map_err(|e| rpc_error(Value::Null, PARSE_ERROR, &format!("Parse error: {}", e)))
+}
+
+/// Handle a parsed JSON-RPC request body (single object or batch array)
+/// against a service's configured methods. Returns `None` when the whole
+/// request resolved to no response at all (a lone notification, or a batch
+/// made up entirely of notifications), per the JSON-RPC 2.0 spec.
+pub fn handle_request(service_config: &ServiceConfig, body: &Value) -> Option<Value> {
+    let methods = match &service_config.service_type {
+        ServiceType::JsonRpc { methods } => methods,
+        _ => return Some(rpc_error(Value::Null, INVALID_REQUEST, "Service is not a JSON-RPC service")),
+    };
+
+    if let Some(batch) = body.as_array() {
+        if batch.is_empty() {
+            return Some(rpc_error(Value::Null, INVALID_REQUEST, "Batch request must not be empty"));
+        }
+
+        let responses: Vec<Value> = batch.iter().filter_map(|single| dispatch_single(methods, single)).collect();
+        return if responses.is_empty() { None } else { Some(Value::Array(responses)) };
+    }
+
+    dispatch_single(methods, body)
+}
+
+fn dispatch_single(methods: &HashMap<String, RpcMethodConfig>, request: &Value) -> Option<Value> {
+    let is_notification = request.get("id").is_none();
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+
+    let Some(obj) = request.as_object() else {
+        return Some(rpc_error(id, INVALID_REQUEST, "Request must be a JSON object"));
+    };
+
+    if obj.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+        return Some(rpc_error(id, INVALID_REQUEST, "Missing or invalid \"jsonrpc\" version"));
+    }
+
+    let Some(method_name) = obj.get("method").and_then(Value::as_str) else {
+        return Some(rpc_error(id, INVALID_REQUEST, "Missing \"method\""));
+    };
+
+    let Some(method_config) = methods.get(method_name) else {
+        return if is_notification {
+            None
+        } else {
+            Some(rpc_error(id, METHOD_NOT_FOUND, &format!("Method '{}' not found", method_name)))
+        };
+    };
+
+    let params = obj.get("params").cloned().unwrap_or(Value::Null);
+
+    if let Err(e) = validate_rpc_params(&params, &method_config.required_params) {
+        return if is_notification { None } else { Some(rpc_error(id, rpc_error_code(&e), &e.to_string())) };
+    }
+
+    match execute_rpc_transformer(&method_config.template, &method_config.transformer, &params) {
+        Ok(result) if is_notification => {
+            let _ = result;
+            None
+        }
+        Ok(result) => Some(rpc_result(id, result)),
+        Err(_) if is_notification => None,
+        Err(e) => {
+            let code = rpc_error_code(&e);
+            Some(rpc_error(id, code, &e.to_string()))
+        }
+    }
+}
+
+/// Check that every name in `required_params` is present and non-null in
+/// `params`, mirroring `utils::validate_parameters`'s required-field check
+/// for REST routes. `params` must be a JSON object for any of this to pass;
+/// a method with no required params accepts any shape (including the
+/// array-params form the JSON-RPC spec also allows).
+fn validate_rpc_params(params: &Value, required_params: &[String]) -> Result<(), MockError> {
+    if required_params.is_empty() {
+        return Ok(());
+    }
+
+    let Some(obj) = params.as_object() else {
+        return Err(MockError::ValidationError("params must be an object when required_params is set".to_string()));
+    };
+
+    for name in required_params {
+        if obj.get(name).map(Value::is_null).unwrap_or(true) {
+            return Err(MockError::ValidationError(format!("Missing required param '{}'", name)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Execute the method's JavaScript transformer in an isolated rquickjs
+/// runtime, mirroring `utils::execute_transformer` but with raw JSON-RPC
+/// `params` (object or array) instead of the string-keyed path params used
+/// by REST dynamic services.
+fn execute_rpc_transformer(template: &Value, transformer_code: &str, params: &Value) -> Result<Value, MockError> {
+    let rt = Runtime::new().map_err(|e| MockError::ParseError(format!("Failed to create JS runtime: {}", e)))?;
+    let ctx = Context::full(&rt).map_err(|e| MockError::ParseError(format!("Failed to create JS context: {}", e)))?;
+
+    ctx.with(|ctx| -> Result<Value, MockError> {
+        let template_str = serde_json::to_string(template)
+            .map_err(|e| MockError::ParseError(format!("Failed to serialize template: {}", e)))?;
+        let params_str = serde_json::to_string(params)
+            .map_err(|e| MockError::ParseError(format!("Failed to serialize params: {}", e)))?;
+
+        let js_code = format!(
+            r#"
+            const template = {};
+            const params = {};
+            const context = {{
+                timestamp: new Date().toISOString(),
+                requestId: Math.random().toString(36).substr(2, 9)
+            }};
+
+            {}
+
+            if (typeof transform !== 'function') {{
+                throw new Error('transform function not defined in transformer');
+            }}
+
+            const result = transform(template, params, context);
+            JSON.stringify(result);
+            "#,
+            template_str, params_str, transformer_code
+        );
+
+        let result: String = ctx
+            .eval(js_code.as_bytes())
+            .map_err(|e| MockError::ParseError(format!("JavaScript execution failed: {}", e)))?;
+
+        serde_json::from_str(&result)
+            .map_err(|e| MockError::ParseError(format!("Invalid JSON returned from transformer: {}", e)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jsonrpc_service(methods: HashMap<String, RpcMethodConfig>) -> ServiceConfig {
+        ServiceConfig {
+            name: "rpc_service".to_string(),
+            service_type: ServiceType::JsonRpc { methods },
+            path: std::path::PathBuf::from("/tmp/rpc_service"),
+            latency: HashMap::new(),
+            faults: HashMap::new(),
+            rules: HashMap::new(),
+            default_headers: HashMap::new(),
+            scope_prefix: None,
+        }
+    }
+
+    fn echo_method() -> RpcMethodConfig {
+        RpcMethodConfig {
+            template: json!({}),
+            transformer: "function transform(template, params, context) { return params; }".to_string(),
+            required_params: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn dispatch_success_wraps_result() {
+        let mut methods = HashMap::new();
+        methods.insert("echo".to_string(), echo_method());
+        let service = jsonrpc_service(methods);
+
+        let request = json!({"jsonrpc": "2.0", "method": "echo", "params": {"a": 1}, "id": 1});
+        let response = handle_request(&service, &request).unwrap();
+
+        assert_eq!(response["jsonrpc"], "2.0");
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["a"], 1);
+    }
+
+    #[test]
+    fn dispatch_notification_produces_no_response() {
+        let mut methods = HashMap::new();
+        methods.insert("echo".to_string(), echo_method());
+        let service = jsonrpc_service(methods);
+
+        let request = json!({"jsonrpc": "2.0", "method": "echo", "params": {}});
+        assert!(handle_request(&service, &request).is_none());
+    }
+
+    #[test]
+    fn dispatch_unknown_method_is_method_not_found() {
+        let service = jsonrpc_service(HashMap::new());
+
+        let request = json!({"jsonrpc": "2.0", "method": "missing", "id": 1});
+        let response = handle_request(&service, &request).unwrap();
+
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn dispatch_missing_jsonrpc_version_is_invalid_request() {
+        let service = jsonrpc_service(HashMap::new());
+
+        let request = json!({"method": "echo", "id": 1});
+        let response = handle_request(&service, &request).unwrap();
+
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+    }
+
+    #[test]
+    fn dispatch_non_rpc_service_is_invalid_request() {
+        let service = ServiceConfig {
+            name: "not_rpc".to_string(),
+            service_type: ServiceType::Static { content: json!({}) },
+            path: std::path::PathBuf::from("/tmp/not_rpc"),
+            latency: HashMap::new(),
+            faults: HashMap::new(),
+            rules: HashMap::new(),
+            default_headers: HashMap::new(),
+            scope_prefix: None,
+        };
+
+        let request = json!({"jsonrpc": "2.0", "method": "echo", "id": 1});
+        let response = handle_request(&service, &request).unwrap();
+
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+    }
+
+    #[test]
+    fn dispatch_transformer_failure_is_internal_error_not_invalid_params() {
+        let mut methods = HashMap::new();
+        methods.insert(
+            "boom".to_string(),
+            RpcMethodConfig {
+                template: json!({}),
+                transformer: "function transform(template, params, context) { throw new Error('boom'); }".to_string(),
+                required_params: Vec::new(),
+            },
+        );
+        let service = jsonrpc_service(methods);
+
+        let request = json!({"jsonrpc": "2.0", "method": "boom", "params": {}, "id": 1});
+        let response = handle_request(&service, &request).unwrap();
+
+        assert_eq!(response["error"]["code"], INTERNAL_ERROR);
+    }
+
+    #[test]
+    fn dispatch_missing_required_param_is_invalid_params() {
+        let mut methods = HashMap::new();
+        methods.insert(
+            "greet".to_string(),
+            RpcMethodConfig {
+                template: json!({}),
+                transformer: "function transform(template, params, context) { return params; }".to_string(),
+                required_params: vec!["name".to_string()],
+            },
+        );
+        let service = jsonrpc_service(methods);
+
+        let request = json!({"jsonrpc": "2.0", "method": "greet", "params": {}, "id": 1});
+        let response = handle_request(&service, &request).unwrap();
+
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn dispatch_present_required_param_reaches_transformer() {
+        let mut methods = HashMap::new();
+        methods.insert(
+            "greet".to_string(),
+            RpcMethodConfig {
+                template: json!({}),
+                transformer: "function transform(template, params, context) { return params; }".to_string(),
+                required_params: vec!["name".to_string()],
+            },
+        );
+        let service = jsonrpc_service(methods);
+
+        let request = json!({"jsonrpc": "2.0", "method": "greet", "params": {"name": "ada"}, "id": 1});
+        let response = handle_request(&service, &request).unwrap();
+
+        assert_eq!(response["result"]["name"], "ada");
+    }
+
+    #[test]
+    fn dispatch_batch_drops_notifications_from_output() {
+        let mut methods = HashMap::new();
+        methods.insert("echo".to_string(), echo_method());
+        let service = jsonrpc_service(methods);
+
+        let request = json!([
+            {"jsonrpc": "2.0", "method": "echo", "params": {"n": 1}, "id": 1},
+            {"jsonrpc": "2.0", "method": "echo", "params": {"n": 2}},
+        ]);
+        let response = handle_request(&service, &request).unwrap();
+
+        let batch = response.as_array().unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0]["id"], 1);
+    }
+
+    #[test]
+    fn dispatch_empty_batch_is_invalid_request() {
+        let service = jsonrpc_service(HashMap::new());
+        let request = json!([]);
+        let response = handle_request(&service, &request).unwrap();
+
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+    }
+
+    #[test]
+    fn parse_request_rejects_malformed_json() {
+        let error = parse_request("not json").unwrap_err();
+        assert_eq!(error["error"]["code"], PARSE_ERROR);
+    }
+}
@@ -0,0 +1,223 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Latency behavior that can be attached to a single mock method.
+///
+/// Loaded from an optional `latency.json` file in a service directory,
+/// keyed by HTTP method (see `utils::load_service_config`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LatencyConfig {
+    /// Always delay by exactly `ms` milliseconds.
+    Fixed { ms: u64 },
+    /// Delay by a value drawn uniformly from `min_ms..=max_ms`.
+    Range { min_ms: u64, max_ms: u64 },
+    /// Delay by a value drawn from one of several weighted buckets,
+    /// e.g. 95% fast + 5% slow tail.
+    Weighted { buckets: Vec<LatencyBucket> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyBucket {
+    pub weight: f64,
+    pub min_ms: u64,
+    pub max_ms: u64,
+}
+
+impl LatencyConfig {
+    /// Draw a single delay sample in milliseconds according to this config.
+    pub fn sample(&self) -> u64 {
+        let mut rng = rand::thread_rng();
+        match self {
+            LatencyConfig::Fixed { ms } => *ms,
+            LatencyConfig::Range { min_ms, max_ms } => sample_range(&mut rng, *min_ms, *max_ms),
+            LatencyConfig::Weighted { buckets } => sample_weighted(&mut rng, buckets),
+        }
+    }
+}
+
+fn sample_range(rng: &mut impl Rng, min_ms: u64, max_ms: u64) -> u64 {
+    if max_ms <= min_ms {
+        return min_ms;
+    }
+    rng.gen_range(min_ms..=max_ms)
+}
+
+fn sample_weighted(rng: &mut impl Rng, buckets: &[LatencyBucket]) -> u64 {
+    let total_weight: f64 = buckets.iter().map(|b| b.weight).sum();
+    if buckets.is_empty() || total_weight <= 0.0 {
+        return 0;
+    }
+
+    let roll: f64 = rng.gen_range(0.0..1.0) * total_weight;
+    let mut cumulative = 0.0;
+    for bucket in buckets {
+        cumulative += bucket.weight;
+        if roll < cumulative {
+            return sample_range(rng, bucket.min_ms, bucket.max_ms);
+        }
+    }
+
+    // Floating point rounding can leave `roll` just past the last boundary;
+    // fall back to the final bucket rather than no delay at all.
+    let last = &buckets[buckets.len() - 1];
+    sample_range(rng, last.min_ms, last.max_ms)
+}
+
+/// Resolve the effective delay for a request: an explicit header override
+/// wins, then the method's own config, then the server-wide `--latency`
+/// default.
+pub fn resolve_delay_ms(
+    header_override: Option<u64>,
+    method_config: Option<&LatencyConfig>,
+    global_default_ms: Option<u64>,
+) -> Option<u64> {
+    header_override
+        .or_else(|| method_config.map(LatencyConfig::sample))
+        .or(global_default_ms)
+}
+
+/// Parse the `X-Mock-Delay-Ms` header value, if present, into a delay.
+pub fn header_override_ms(req: &actix_web::HttpRequest) -> Option<u64> {
+    req.headers()
+        .get("X-Mock-Delay-Ms")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.parse::<u64>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_delay_ms_prefers_header_override() {
+        let method_config = LatencyConfig::Fixed { ms: 50 };
+        assert_eq!(resolve_delay_ms(Some(10), Some(&method_config), Some(100)), Some(10));
+    }
+
+    #[test]
+    fn resolve_delay_ms_falls_back_to_method_config() {
+        let method_config = LatencyConfig::Fixed { ms: 50 };
+        assert_eq!(resolve_delay_ms(None, Some(&method_config), Some(100)), Some(50));
+    }
+
+    #[test]
+    fn resolve_delay_ms_falls_back_to_global_default() {
+        assert_eq!(resolve_delay_ms(None, None, Some(100)), Some(100));
+    }
+
+    #[test]
+    fn resolve_delay_ms_none_when_nothing_configured() {
+        assert_eq!(resolve_delay_ms(None, None, None), None);
+    }
+
+    #[test]
+    fn fixed_latency_always_samples_the_same_value() {
+        let config = LatencyConfig::Fixed { ms: 250 };
+        for _ in 0..20 {
+            assert_eq!(config.sample(), 250);
+        }
+    }
+
+    #[test]
+    fn range_latency_samples_within_bounds() {
+        let config = LatencyConfig::Range { min_ms: 10, max_ms: 20 };
+        for _ in 0..50 {
+            let sample = config.sample();
+            assert!((10..=20).contains(&sample), "sample {} out of range", sample);
+        }
+    }
+
+    #[test]
+    fn range_latency_with_inverted_bounds_returns_min() {
+        let config = LatencyConfig::Range { min_ms: 30, max_ms: 10 };
+        assert_eq!(config.sample(), 30);
+    }
+
+    #[test]
+    fn weighted_latency_samples_within_some_bucket() {
+        let config = LatencyConfig::Weighted {
+            buckets: vec![
+                LatencyBucket { weight: 0.95, min_ms: 1, max_ms: 5 },
+                LatencyBucket { weight: 0.05, min_ms: 100, max_ms: 200 },
+            ],
+        };
+        for _ in 0..50 {
+            let sample = config.sample();
+            assert!((1..=5).contains(&sample) || (100..=200).contains(&sample), "sample {} out of range", sample);
+        }
+    }
+
+    #[test]
+    fn weighted_latency_with_no_buckets_samples_zero() {
+        let config = LatencyConfig::Weighted { buckets: vec![] };
+        assert_eq!(config.sample(), 0);
+    }
+
+    #[test]
+    fn resolve_fault_absent_config_never_faults() {
+        assert!(resolve_fault(None).is_none());
+    }
+
+    #[test]
+    fn resolve_fault_zero_rate_never_faults() {
+        let config = FaultConfig { rate: 0.0, action: FaultAction::Status { status: 500 } };
+        for _ in 0..20 {
+            assert!(resolve_fault(Some(&config)).is_none());
+        }
+    }
+
+    #[test]
+    fn resolve_fault_full_rate_always_faults() {
+        let config = FaultConfig { rate: 1.0, action: FaultAction::Status { status: 503 } };
+        for _ in 0..20 {
+            match resolve_fault(Some(&config)) {
+                Some(FaultAction::Status { status }) => assert_eq!(status, 503),
+                other => panic!("expected a Status fault, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn resolve_fault_clamps_out_of_range_rate() {
+        let config = FaultConfig { rate: 5.0, action: FaultAction::Timeout { hang_ms: 10 } };
+        assert!(resolve_fault(Some(&config)).is_some());
+    }
+}
+
+/// Fault-injection behavior for a single mock method: with probability
+/// `rate`, serve `action` instead of the method's normal response.
+///
+/// Loaded from an optional `faults.json` file in a service directory, keyed
+/// by HTTP method, alongside `latency.json` — inspired by how pict-rs gates
+/// work behind its queue/semaphore layer to exercise backpressure handling.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FaultConfig {
+    /// Probability in `[0.0, 1.0]` that a request hits the fault instead of
+    /// its normal response.
+    pub rate: f64,
+    pub action: FaultAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FaultAction {
+    /// Respond immediately with `status` instead of the normal mock body.
+    Status { status: u16 },
+    /// Hold the connection open for `hang_ms` to simulate a genuinely slow
+    /// or hung upstream, then finally give up with `504 Gateway Timeout`.
+    Timeout { hang_ms: u64 },
+}
+
+/// Roll the dice against a method's configured fault rate, returning the
+/// action to take when it's a hit. Absent config means the method never
+/// faults.
+pub fn resolve_fault(method_config: Option<&FaultConfig>) -> Option<FaultAction> {
+    let config = method_config?;
+    let mut rng = rand::thread_rng();
+    if rng.gen_bool(config.rate.clamp(0.0, 1.0)) {
+        Some(config.action.clone())
+    } else {
+        None
+    }
+}
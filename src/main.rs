@@ -1,11 +1,21 @@
+mod auth;
+mod cors;
 mod handlers;
+mod jsonrpc;
+mod latency;
+mod matching;
+mod metrics;
+mod proxy;
+mod routing;
+mod store;
+mod test_harness;
 mod utils;
+mod watch;
 
-use actix_cors::Cors;
-use actix_web::{web, App, HttpServer, middleware::Logger};
+use actix_web::{web, App, HttpServer, middleware::{Compress, Logger}};
 use clap::Parser;
 use env_logger::Env;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -17,6 +27,82 @@ struct Args {
     /// Host to bind the server to
     #[arg(long, default_value = "127.0.0.1")]
     host: String,
+
+    /// Default artificial latency (in milliseconds) applied to every mock
+    /// response that doesn't declare its own `latency.json` behavior
+    #[arg(long)]
+    latency: Option<u64>,
+
+    /// Path to a PEM-encoded TLS certificate chain. Requires --tls-key.
+    #[arg(long)]
+    tls_cert: Option<String>,
+
+    /// Path to a PEM-encoded TLS private key. Requires --tls-cert.
+    #[arg(long)]
+    tls_key: Option<String>,
+
+    /// Path to a PEM-encoded CA bundle used to verify client certificates.
+    /// Only meaningful together with --tls-cert/--tls-key; enables mutual TLS.
+    #[arg(long)]
+    client_ca: Option<String>,
+
+    /// Comma-separated bearer token(s) required to call the
+    /// /api/services* management endpoints. Falls back to the
+    /// MOCK_API_TOKEN env var; when neither is set, the management
+    /// endpoints remain open (today's behavior).
+    #[arg(long)]
+    api_token: Option<String>,
+
+    /// Comma-separated bearer token(s) required to read mock responses
+    /// (the plain service routes, as opposed to /api/services*
+    /// management). Falls back to the MOCK_READ_TOKEN env var; when
+    /// neither is set, reads stay open, so existing deployments keep
+    /// working without configuring this.
+    #[arg(long)]
+    read_token: Option<String>,
+
+    /// Base URL of a live backend to proxy unmatched requests to. Requires
+    /// --record to persist the captured responses as fixtures.
+    #[arg(long)]
+    upstream: Option<String>,
+
+    /// Record responses forwarded via --upstream as mock fixtures on disk.
+    #[arg(long)]
+    record: bool,
+
+    /// When recording, overwrite fixtures that already exist instead of
+    /// leaving them intact.
+    #[arg(long)]
+    record_overwrite: bool,
+
+    /// Path to a JSON file describing the CORS policy to enforce. When
+    /// omitted, falls back to today's permissive allow-everything default.
+    #[arg(long)]
+    cors_config: Option<String>,
+
+    /// Run every service's `*.test.js` transformer tests and exit instead of
+    /// starting the server. Exits nonzero if any test failed.
+    #[arg(long)]
+    test: bool,
+
+    /// Watch the services directory and hot-reload changed services into
+    /// the running server instead of requiring a restart.
+    #[arg(long)]
+    watch: bool,
+
+    /// Name of an S3-compatible bucket to use as the storage backend for
+    /// mock bodies (served, uploaded, recorded, and listed) and for service
+    /// create/delete, instead of the local filesystem. Service discovery
+    /// still reads from local disk. Credentials and region are read from
+    /// the usual AWS_* environment variables.
+    #[arg(long)]
+    s3_bucket: Option<String>,
+
+    /// Custom S3-compatible endpoint (e.g. for MinIO or another
+    /// S3-compatible provider) to use instead of AWS's default endpoint.
+    /// Only meaningful together with --s3-bucket.
+    #[arg(long)]
+    s3_endpoint: Option<String>,
 }
 
 #[actix_web::main]
@@ -26,14 +112,23 @@ async fn main() -> std::io::Result<()> {
     // Initialize logger
     env_logger::init_from_env(Env::default().default_filter_or("info"));
 
+    // Structured per-request spans (resolved service name, dynamic vs.
+    // legacy-static route resolution) are emitted separately via `tracing`;
+    // this subscriber is what actually prints them.
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .init();
+
     log::info!("Starting Mock Service on {}:{}", args.host, args.port);
 
     // Discover and load all services
     log::info!("Discovering services...");
-    let service_registry = match utils::discover_services() {
+    let registry = match utils::discover_services() {
         Ok(registry) => {
             log::info!("Successfully loaded {} services", registry.services.len());
-            Arc::new(registry)
+            registry
         }
         Err(e) => {
             log::error!("Failed to discover services: {}", e);
@@ -41,42 +136,207 @@ async fn main() -> std::io::Result<()> {
         }
     };
 
+    if args.test {
+        let summary = test_harness::run_service_tests(&registry);
+        for event in &summary.events {
+            println!("{}", serde_json::to_string(event).unwrap());
+        }
+        log::info!(
+            "Transformer tests finished: {} passed, {} failed, {} ignored",
+            summary.passed, summary.failed, summary.ignored
+        );
+        std::process::exit(summary.exit_code());
+    }
+
+    let service_registry: utils::SharedRegistry = Arc::new(RwLock::new(registry));
+
+    // Kept alive for the lifetime of `main` so the background watch thread
+    // keeps running; dropping it would stop the watch.
+    let _watcher = if args.watch {
+        match watch::watch_services(service_registry.clone()) {
+            Ok(watcher) => {
+                log::info!("Hot-reload enabled; watching services directory for changes");
+                Some(watcher)
+            }
+            Err(e) => {
+                log::error!("Failed to start filesystem watcher: {}; hot-reload disabled", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
     // Clone registry for the HttpServer closure
     let registry_clone = service_registry.clone();
+    let global_latency_ms = args.latency;
+    let api_token = args.api_token.clone().or_else(|| std::env::var("MOCK_API_TOKEN").ok());
+    let management_tokens = auth::parse_tokens(api_token.as_deref());
+    if management_tokens.is_some() {
+        log::info!("API token guard enabled for /api/services* management endpoints");
+    } else {
+        log::warn!("No API token configured; management endpoints are open to anyone who can reach this server");
+    }
 
-    HttpServer::new(move || {
-        let cors = Cors::default()
-            .allow_any_origin()
-            .allow_any_method()
-            .allow_any_header()
-            .max_age(3600);
+    let read_token = args.read_token.clone().or_else(|| std::env::var("MOCK_READ_TOKEN").ok());
+    let read_tokens = auth::parse_tokens(read_token.as_deref());
+    if read_tokens.is_some() {
+        log::info!("Read token guard enabled for mock response endpoints");
+    }
+
+    let proxy_config = args.upstream.clone().map(|upstream| {
+        log::info!(
+            "Proxy mode enabled, forwarding unmatched requests to {} (record={})",
+            upstream, args.record
+        );
+        proxy::ProxyConfig {
+            upstream,
+            record: args.record,
+            overwrite: args.record_overwrite,
+        }
+    });
+
+    let cors_config_path = args.cors_config.clone();
+
+    let store: std::sync::Arc<dyn store::Store> = match &args.s3_bucket {
+        Some(bucket) => match store::S3Store::new(bucket, args.s3_endpoint.as_deref()) {
+            Ok(s3_store) => {
+                log::info!(
+                    "Using S3-compatible object store backend (bucket={}); mock bodies are served from and recorded to this bucket, but service discovery (routes/templates/latency/fault/rule/guard config) still loads from local disk",
+                    bucket
+                );
+                std::sync::Arc::new(s3_store)
+            }
+            Err(e) => {
+                log::error!("Failed to configure S3 store: {}; falling back to the local filesystem", e);
+                std::sync::Arc::new(store::FsStore::new(utils::DEFAULT_STORE.clone()))
+            }
+        },
+        None => std::sync::Arc::new(store::FsStore::new(utils::DEFAULT_STORE.clone())),
+    };
+    let store_clone = store.clone();
+
+    let server = HttpServer::new(move || {
+        let cors_policy = cors::build_cors(cors_config_path.as_deref());
+
+        let management_scope = web::scope("/api/services")
+            .wrap(auth::ApiTokenGuard::new(management_tokens.clone(), "management"))
+            .route("", web::get().to(handlers::list_services))
+            .route("/{service}", web::post().to(handlers::create_service))
+            .route("/{service}/{method}", web::put().to(handlers::upload_mock_file))
+            .route("/{service}", web::delete().to(handlers::delete_service));
 
         let mut app = App::new()
             .app_data(web::Data::new(registry_clone.clone()))
-            .wrap(cors)
+            .app_data(web::Data::new(global_latency_ms))
+            .app_data(web::Data::new(proxy_config.clone()))
+            .app_data(web::Data::new(store_clone.clone()))
+            .wrap(cors_policy)
             .wrap(Logger::default())
-            // Utility endpoints for service management
-            .route("/api/services", web::get().to(handlers::list_services))
-            .route("/api/services/{service}", web::post().to(handlers::create_service))
-            .route("/api/services/{service}/{method}", web::put().to(handlers::upload_mock_file))
-            .route("/api/services/{service}", web::delete().to(handlers::delete_service))
+            .wrap(Compress::default())
+            .wrap(metrics::RequestMetrics)
+            // Utility endpoints for service management (guarded by ApiTokenGuard)
+            .service(management_scope)
             .route("/api/health", web::get().to(handlers::health_check))
-            // Dynamic route handler (catches all paths)
+            .route("/metrics", web::get().to(handlers::metrics_endpoint));
+
+        // Services that declare a `scope.json` prefix get their own isolated
+        // `web::scope`, so scope-local middleware can be attached per service
+        // instead of everything funneling through the catch-all handler.
+        // Scopes are wired up once at server start; a service added later by
+        // hot-reload (`--watch`) gets no scope of its own and is served only
+        // through the catch-all handler below.
+        //
+        // Within a scope, each mockable method is registered as its own
+        // `Route` instead of a single blanket `default_service` — actix's
+        // own `Route` model standing in for method dispatch instead of
+        // leaving it entirely to the `req.method()` lookups inside the
+        // handler.
+        for service in registry_clone.read().unwrap().services.values() {
+            if let Some(prefix) = &service.scope_prefix {
+                let mut scope = web::scope(prefix).wrap(auth::ApiTokenGuard::new(read_tokens.clone(), "read"));
+                for method in routing::MOCKABLE_METHODS {
+                    scope = scope.route("/{path:.*}", routing::method_route(method, handlers::handle_dynamic_request));
+                }
+                app = app.service(scope.default_service(web::route().to(handlers::handle_dynamic_request)));
+            }
+        }
+
+        // Plain mock-serving routes: a single dynamic catch-all per method,
+        // opt-in via --read-token/MOCK_READ_TOKEN so existing open
+        // deployments keep working unless they configure one.
+        //
+        // There's no separate `/{service}` resource here: `{path:.*}`'s
+        // regex already matches single-segment paths, so it would absorb
+        // every request before a sibling `/{service}` route ever got a
+        // chance to run — `handle_dynamic_request`'s own single-segment
+        // fallback covers the same legacy static/JSON-RPC lookup instead.
+        let read_scope = web::scope("")
+            .wrap(auth::ApiTokenGuard::new(read_tokens.clone(), "read"))
             .route("/{path:.*}", web::get().to(handlers::handle_dynamic_request))
             .route("/{path:.*}", web::post().to(handlers::handle_dynamic_request))
             .route("/{path:.*}", web::put().to(handlers::handle_dynamic_request))
-            .route("/{path:.*}", web::delete().to(handlers::handle_dynamic_request));
+            .route("/{path:.*}", web::delete().to(handlers::handle_dynamic_request))
+            .route("/{path:.*}", web::patch().to(handlers::handle_dynamic_request))
+            .route("/{path:.*}", web::head().to(handlers::handle_dynamic_request))
+            .route("/{path:.*}", web::method(actix_web::http::Method::OPTIONS).to(handlers::handle_dynamic_request));
 
-        // Legacy static routes for backward compatibility
-        app = app
-            .route("/{service}", web::get().to(handlers::handle_mock_request))
-            .route("/{service}", web::post().to(handlers::handle_mock_request))
-            .route("/{service}", web::put().to(handlers::handle_mock_request))
-            .route("/{service}", web::delete().to(handlers::handle_mock_request));
+        app = app.service(read_scope);
 
         app
-    })
-    .bind((args.host, args.port))?
-    .run()
-    .await
+    });
+
+    let server = match (&args.tls_cert, &args.tls_key) {
+        (Some(cert_path), Some(key_path)) => {
+            log::info!("TLS enabled, loading certificate from {}", cert_path);
+            let tls_config = build_tls_config(cert_path, key_path, args.client_ca.as_deref())
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to configure TLS: {}", e)))?;
+            server.bind_rustls_0_23((args.host, args.port), tls_config)?
+        }
+        (None, None) => server.bind((args.host, args.port))?,
+        (Some(_), None) | (None, Some(_)) => {
+            // Refuse to start rather than silently falling back to plain
+            // HTTP: a caller who set one of these flags meant to enable TLS,
+            // and serving mocks over plain HTTP instead is the kind of
+            // misconfiguration that should fail loudly, not downgrade quietly.
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "--tls-cert and --tls-key must both be provided together; got only one",
+            ));
+        }
+    };
+
+    server.run().await
+}
+
+/// Build the rustls server configuration used for TLS termination, optionally
+/// requiring and verifying client certificates (mutual TLS) when a client CA
+/// bundle is provided.
+fn build_tls_config(
+    cert_path: &str,
+    key_path: &str,
+    client_ca_path: Option<&str>,
+) -> Result<rustls::ServerConfig, utils::MockError> {
+    let cert_chain = utils::load_cert(cert_path)?;
+    let private_key = utils::load_private_key(key_path)?;
+
+    let config_builder = rustls::ServerConfig::builder();
+
+    let config = if let Some(ca_path) = client_ca_path {
+        log::info!("Mutual TLS enabled, verifying clients against {}", ca_path);
+        let client_ca_store = utils::load_client_ca(ca_path)?;
+        let client_verifier = rustls::server::WebPkiClientVerifier::builder(Arc::new(client_ca_store))
+            .build()
+            .map_err(|e| utils::MockError::ParseError(format!("Invalid client CA configuration: {}", e)))?;
+        config_builder
+            .with_client_cert_verifier(client_verifier)
+            .with_single_cert(cert_chain, private_key)
+    } else {
+        config_builder
+            .with_no_client_auth()
+            .with_single_cert(cert_chain, private_key)
+    }
+    .map_err(|e| utils::MockError::ParseError(format!("Invalid TLS certificate/key pair: {}", e)))?;
+
+    Ok(config)
 }
\ No newline at end of file
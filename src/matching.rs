@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One candidate response for a `(service, method)` pair: served when every
+/// configured matcher passes against the incoming request. A rule with no
+/// matchers set (every field `None`) always matches, so a rule list's last
+/// entry can act as a declared default/catch-all ahead of the file-based
+/// fallback.
+///
+/// Loaded from an optional `rules.json` file in a service directory, keyed
+/// by HTTP method to a list of rules evaluated in declared order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchRule {
+    /// Deep subset match against the parsed JSON request body: every key in
+    /// `body` must be present in the request body with an equal value
+    /// (nested objects recurse the same way); extra keys in the request
+    /// body are ignored.
+    #[serde(default)]
+    pub body: Option<Value>,
+    /// Exact-match query parameters the request must carry.
+    #[serde(default)]
+    pub query: Option<HashMap<String, String>>,
+    /// Exact-match headers the request must carry.
+    #[serde(default)]
+    pub headers: Option<HashMap<String, String>>,
+    /// Exact-match path parameters, reusing the params already extracted by
+    /// `utils::match_dynamic_route` (e.g. `{"id": "42"}`).
+    #[serde(default)]
+    pub params: Option<HashMap<String, String>>,
+    /// The response body to serve when this rule matches.
+    pub response: Value,
+    /// HTTP status to respond with; defaults to 200 when absent.
+    #[serde(default)]
+    pub status: Option<u16>,
+}
+
+/// The parts of an incoming request a `MatchRule` can be evaluated against.
+pub struct MatchRequest<'a> {
+    pub body: Option<&'a Value>,
+    pub query: &'a HashMap<String, String>,
+    pub headers: &'a HashMap<String, String>,
+    pub params: &'a HashMap<String, String>,
+}
+
+/// Evaluate `rules` in declared order and return the first whose matchers
+/// all pass against `request`. Pure function of its inputs, so it's
+/// unit-testable without an actix request in hand.
+pub fn select_response<'a>(rules: &'a [MatchRule], request: &MatchRequest) -> Option<&'a MatchRule> {
+    rules.iter().find(|rule| rule_matches(rule, request))
+}
+
+fn rule_matches(rule: &MatchRule, request: &MatchRequest) -> bool {
+    let body_ok = match &rule.body {
+        None => true,
+        Some(expected) => request.body.map(|actual| json_subset_matches(actual, expected)).unwrap_or(false),
+    };
+
+    body_ok
+        && map_subset_matches(rule.query.as_ref(), request.query)
+        && map_subset_matches(rule.headers.as_ref(), request.headers)
+        && map_subset_matches(rule.params.as_ref(), request.params)
+}
+
+/// `expected` is `None` (matcher not configured) or every one of its
+/// key/value pairs is present unchanged in `actual`; extra keys in `actual`
+/// are ignored.
+fn map_subset_matches(expected: Option<&HashMap<String, String>>, actual: &HashMap<String, String>) -> bool {
+    match expected {
+        None => true,
+        Some(expected) => expected.iter().all(|(key, value)| actual.get(key) == Some(value)),
+    }
+}
+
+/// Deep subset comparison: every key in `expected` must be present in
+/// `actual` with an equal value; nested objects recurse the same way;
+/// non-object values must match exactly. Extra keys in `actual` are
+/// ignored, so a rule only needs to declare the fields it cares about.
+fn json_subset_matches(actual: &Value, expected: &Value) -> bool {
+    match (expected, actual) {
+        (Value::Object(expected_map), Value::Object(actual_map)) => expected_map.iter().all(|(key, expected_value)| {
+            actual_map
+                .get(key)
+                .is_some_and(|actual_value| json_subset_matches(actual_value, expected_value))
+        }),
+        _ => expected == actual,
+    }
+}
+
+/// Parse a raw query string into a `key -> value` map, keeping only the
+/// first occurrence of a repeated key. Deliberately skips percent-decoding,
+/// matching `auth::query_param`'s reasoning: match rules are authored
+/// against plain values, not encoded ones.
+pub fn parse_query_string(query: &str) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+    for pair in query.split('&') {
+        if let Some((name, value)) = pair.split_once('=') {
+            params.entry(name.to_string()).or_insert_with(|| value.to_string());
+        }
+    }
+    params
+}
+
+/// Flatten an actix request's headers into a `name -> value` map, dropping
+/// any header whose value isn't valid UTF-8.
+pub fn header_map(req: &actix_web::HttpRequest) -> HashMap<String, String> {
+    req.headers()
+        .iter()
+        .filter_map(|(name, value)| value.to_str().ok().map(|v| (name.as_str().to_string(), v.to_string())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule(body: Option<Value>, response: Value) -> MatchRule {
+        MatchRule { body, query: None, headers: None, params: None, response, status: None }
+    }
+
+    #[test]
+    fn empty_matcher_always_matches() {
+        let rules = vec![rule(None, json!({"default": true}))];
+        let query = HashMap::new();
+        let headers = HashMap::new();
+        let params = HashMap::new();
+        let request = MatchRequest { body: None, query: &query, headers: &headers, params: &params };
+
+        let matched = select_response(&rules, &request).expect("empty matcher should match");
+        assert_eq!(matched.response, json!({"default": true}));
+    }
+
+    #[test]
+    fn body_subset_matches_ignoring_extra_keys() {
+        let rules = vec![rule(Some(json!({"type": "premium"})), json!({"tier": "gold"}))];
+        let query = HashMap::new();
+        let headers = HashMap::new();
+        let params = HashMap::new();
+        let body = json!({"type": "premium", "id": 42});
+        let request = MatchRequest { body: Some(&body), query: &query, headers: &headers, params: &params };
+
+        assert!(select_response(&rules, &request).is_some());
+    }
+
+    #[test]
+    fn body_mismatch_falls_through_to_next_rule() {
+        let rules = vec![
+            rule(Some(json!({"type": "premium"})), json!({"tier": "gold"})),
+            rule(None, json!({"tier": "standard"})),
+        ];
+        let query = HashMap::new();
+        let headers = HashMap::new();
+        let params = HashMap::new();
+        let body = json!({"type": "basic"});
+        let request = MatchRequest { body: Some(&body), query: &query, headers: &headers, params: &params };
+
+        let matched = select_response(&rules, &request).expect("default rule should match");
+        assert_eq!(matched.response, json!({"tier": "standard"}));
+    }
+
+    #[test]
+    fn first_match_wins() {
+        let rules = vec![rule(None, json!({"which": "first"})), rule(None, json!({"which": "second"}))];
+        let query = HashMap::new();
+        let headers = HashMap::new();
+        let params = HashMap::new();
+        let request = MatchRequest { body: None, query: &query, headers: &headers, params: &params };
+
+        let matched = select_response(&rules, &request).unwrap();
+        assert_eq!(matched.response, json!({"which": "first"}));
+    }
+}
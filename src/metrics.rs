@@ -0,0 +1,159 @@
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::time::Instant;
+
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::Error;
+use once_cell::sync::Lazy;
+use prometheus::{Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder};
+
+use crate::utils::MockError;
+
+static REGISTRY: Lazy<Registry> = Lazy::new(Registry::new);
+
+static HTTP_REQUESTS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new("mock_http_requests_total", "Total HTTP requests handled, labeled by method and status"),
+        &["method", "status"],
+    )
+    .expect("metric definition is valid");
+    REGISTRY.register(Box::new(counter.clone())).expect("metric registered once");
+    counter
+});
+
+static HTTP_REQUEST_DURATION_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    let histogram = HistogramVec::new(
+        HistogramOpts::new(
+            "mock_http_request_duration_seconds",
+            "HTTP request latency in seconds, labeled by method and status",
+        ),
+        &["method", "status"],
+    )
+    .expect("metric definition is valid");
+    REGISTRY.register(Box::new(histogram.clone())).expect("metric registered once");
+    histogram
+});
+
+static MOCK_REQUESTS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "mock_service_requests_total",
+            "Mock responses served, labeled by service name, HTTP method, and outcome",
+        ),
+        &["service", "method", "outcome"],
+    )
+    .expect("metric definition is valid");
+    REGISTRY.register(Box::new(counter.clone())).expect("metric registered once");
+    counter
+});
+
+/// Outcome label for a failed mock lookup, mirroring `MockError`'s variants.
+/// The other outcomes (`"hit"`, `"rule_match"`, `"fault_injected"`) are
+/// recorded directly by the caller, since there's no `MockError` value to
+/// match on in those cases.
+pub fn mock_error_label(error: &MockError) -> &'static str {
+    match error {
+        MockError::FileNotFound(_) => "file_not_found",
+        MockError::ParseError(_) => "parse_error",
+        MockError::IoError(_) => "io_error",
+        MockError::ValidationError(_) => "validation_error",
+    }
+}
+
+/// Record one resolved mock request against the per-service/outcome
+/// counter. Handlers call this directly (rather than the generic HTTP
+/// middleware below) because only they know which service and outcome a
+/// request actually resolved to.
+pub fn record_mock_outcome(service_name: &str, method: &str, outcome: &'static str) {
+    MOCK_REQUESTS_TOTAL.with_label_values(&[service_name, method, outcome]).inc();
+}
+
+/// Render every registered metric in Prometheus text exposition format, for
+/// the `/metrics` endpoint.
+pub fn render() -> Result<String, MockError> {
+    let metric_families = REGISTRY.gather();
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&metric_families, &mut buffer)
+        .map_err(|e| MockError::IoError(format!("Failed to encode metrics: {}", e)))?;
+
+    String::from_utf8(buffer).map_err(|e| MockError::ParseError(format!("Metrics output wasn't valid UTF-8: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_error_label_maps_every_variant() {
+        assert_eq!(mock_error_label(&MockError::FileNotFound("x".into())), "file_not_found");
+        assert_eq!(mock_error_label(&MockError::ParseError("x".into())), "parse_error");
+        assert_eq!(mock_error_label(&MockError::IoError("x".into())), "io_error");
+        assert_eq!(mock_error_label(&MockError::ValidationError("x".into())), "validation_error");
+    }
+
+    #[test]
+    fn render_includes_recorded_outcome() {
+        record_mock_outcome("metrics_test_service", "GET", "hit");
+        let rendered = render().expect("rendering metrics should not fail");
+
+        assert!(rendered.contains("mock_service_requests_total"));
+        assert!(rendered.contains("metrics_test_service"));
+    }
+}
+
+/// Actix middleware recording request count and latency for every request
+/// that reaches the app, labeled by method and response status. Wraps the
+/// whole app so no individual route has to opt in.
+#[derive(Clone, Default)]
+pub struct RequestMetrics;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestMetricsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestMetricsMiddleware { service: Rc::new(service) }))
+    }
+}
+
+pub struct RequestMetricsMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestMetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let method = req.method().to_string();
+        let service = self.service.clone();
+        let start = Instant::now();
+
+        Box::pin(async move {
+            let response = service.call(req).await?;
+            let status = response.status().as_u16().to_string();
+            let elapsed = start.elapsed().as_secs_f64();
+
+            HTTP_REQUESTS_TOTAL.with_label_values(&[&method, &status]).inc();
+            HTTP_REQUEST_DURATION_SECONDS.with_label_values(&[&method, &status]).observe(elapsed);
+
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,144 @@
+use actix_web::http::Method;
+use std::collections::HashMap;
+
+use crate::store::Store;
+use crate::utils::{self, MockError};
+
+/// Headers that are connection-specific and must never be replayed or
+/// persisted alongside a recorded fixture.
+const HOP_BY_HOP_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// Runtime configuration for proxy/record mode, built from the `--record`,
+/// `--upstream`, and `--record-overwrite` CLI flags.
+#[derive(Debug, Clone)]
+pub struct ProxyConfig {
+    pub upstream: String,
+    pub record: bool,
+    pub overwrite: bool,
+}
+
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    pub content_type: String,
+    pub body: Vec<u8>,
+}
+
+/// Whether a header should be carried into a replayed/recorded response:
+/// excludes connection-specific hop-by-hop headers and `Content-Type`,
+/// which is tracked separately rather than alongside the other headers.
+fn is_replayable_header(name: &str) -> bool {
+    let name = name.to_lowercase();
+    !HOP_BY_HOP_HEADERS.contains(&name.as_str()) && name != "content-type"
+}
+
+/// Build the full upstream URL for a forwarded request: `upstream` plus the
+/// original request path and query string, with no double slash where
+/// `upstream` carries a trailing one.
+fn upstream_url(upstream: &str, request_path: &str, query_string: &str) -> String {
+    format!(
+        "{}{}{}",
+        upstream.trim_end_matches('/'),
+        request_path,
+        if query_string.is_empty() { String::new() } else { format!("?{}", query_string) }
+    )
+}
+
+/// Forward a request that has no matching local mock to the configured
+/// upstream, returning its response as raw bytes plus whatever
+/// `Content-Type` it carried, so a non-JSON upstream (SOAP/XML, plain text,
+/// binary) is replayed faithfully instead of forced through JSON. When
+/// `config.record` is set, also persist the captured status/headers/body
+/// through `store` — the same `Arc<dyn Store>` every read already goes
+/// through, so a recording lands wherever `--s3-bucket` (or local disk,
+/// absent that flag) says mock bodies live, instead of always hitting disk.
+pub async fn forward_and_record(
+    config: &ProxyConfig,
+    store: &dyn Store,
+    method: &Method,
+    request_path: &str,
+    query_string: &str,
+    body: &[u8],
+    service_name: &str,
+) -> Result<RecordedResponse, MockError> {
+    let url = upstream_url(&config.upstream, request_path, query_string);
+
+    log::info!("Proxying {} {} -> {}", method, request_path, url);
+
+    let client = awc::Client::new();
+    let mut response = client
+        .request(method.clone(), &url)
+        .send_body(body.to_vec())
+        .await
+        .map_err(|e| MockError::IoError(format!("Upstream request to {} failed: {}", url, e)))?;
+
+    let status = response.status().as_u16();
+    // Content-Type is recorded separately (as it is for every other mock
+    // body, in the `.meta.json` sidecar's `content_type` field written by
+    // `write_response_meta_via_store`), not alongside the other replayed
+    // headers, so it isn't captured twice.
+    let content_type = response
+        .headers()
+        .get(actix_web::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("application/octet-stream")
+        .to_string();
+    let headers: HashMap<String, String> = response
+        .headers()
+        .iter()
+        .filter(|(name, _)| is_replayable_header(name.as_str()))
+        .filter_map(|(name, value)| value.to_str().ok().map(|v| (name.to_string(), v.to_string())))
+        .collect();
+
+    let raw_body = response
+        .body()
+        .await
+        .map_err(|e| MockError::IoError(format!("Failed to read upstream body from {}: {}", url, e)))?;
+
+    if config.record {
+        let method_str = method.as_str().to_uppercase();
+        if config.overwrite || !utils::mock_file_exists_via_store(store, service_name, &method_str).await {
+            utils::save_mock_body_via_store(store, service_name, &method_str, &raw_body, Some(content_type.as_str())).await?;
+            utils::write_response_meta_via_store(store, service_name, &method_str, status, &headers, Some(content_type.as_str())).await?;
+            log::info!("Recorded fixture for {} {} from upstream", method_str, service_name);
+        } else {
+            log::debug!(
+                "Fixture for {} {} already exists; skipping (pass --record-overwrite to replace)",
+                method_str, service_name
+            );
+        }
+    }
+
+    Ok(RecordedResponse { status, headers, content_type, body: raw_body.to_vec() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upstream_url_joins_path_without_double_slash() {
+        assert_eq!(upstream_url("http://localhost:9000/", "/users/1", ""), "http://localhost:9000/users/1");
+    }
+
+    #[test]
+    fn upstream_url_appends_query_string() {
+        assert_eq!(upstream_url("http://localhost:9000", "/users", "id=1"), "http://localhost:9000/users?id=1");
+    }
+
+    #[test]
+    fn is_replayable_header_excludes_hop_by_hop_and_content_type() {
+        assert!(!is_replayable_header("Connection"));
+        assert!(!is_replayable_header("content-type"));
+        assert!(is_replayable_header("X-Request-Id"));
+    }
+}
@@ -0,0 +1,24 @@
+use actix_web::http::Method;
+use actix_web::{web, FromRequest, Handler, Responder, Route};
+
+/// HTTP methods a service can register an explicit route for. Anything
+/// outside this set falls through to the scope's `default_service`
+/// catch-all.
+pub const MOCKABLE_METHODS: [&str; 7] = ["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"];
+
+/// Build the `Route` actix should register for `method`: a route guarded to
+/// that HTTP method. The handler it points at still selects the mock file by
+/// `req.method()`, so the stored file name still decides which response a
+/// method gets. Per-request response variation (e.g. by header) is
+/// handled downstream by `matching::MatchRule`'s `rules.json`, not by
+/// route-level guards, so a request matching no rule simply falls through
+/// to the handler's normal mock lookup.
+pub fn method_route<F, Args>(method: &str, handler: F) -> Route
+where
+    F: Handler<Args>,
+    Args: FromRequest + 'static,
+    F::Output: Responder + 'static,
+{
+    let http_method = method.parse::<Method>().unwrap_or(Method::GET);
+    web::method(http_method).to(handler)
+}
@@ -0,0 +1,238 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use object_store::aws::AmazonS3Builder;
+use object_store::path::Path as ObjectPath;
+use object_store::ObjectStore;
+use futures_util::StreamExt;
+
+use crate::utils::{MockError, ServiceStore};
+
+/// Abstraction over where a service's mock *bodies* live, modeled on
+/// pict-rs's file-store/object-store split: the read/write/list paths that
+/// actually serve and record mock content go through this trait instead of
+/// touching the filesystem directly, so those bodies can be managed
+/// out-of-band in a shared object store instead of local disk.
+///
+/// This covers mock body content only (`save_mock_body`/`read_mock_body`
+/// and their `.meta.json` sidecar), not a service's configuration — routes,
+/// templates, transformers, and the `latency.json`/`faults.json`/
+/// `rules.json` sidecars are discovered by `discover_services` and always
+/// read from local disk, store backend notwithstanding.
+///
+/// A `namespace` is a service's mock directory (e.g. `"users"`); a `key` is
+/// a file within it (e.g. `"users-GET.json"`), matching the on-disk layout
+/// `ServiceStore` already uses.
+#[async_trait]
+pub trait Store: Send + Sync {
+    async fn read(&self, namespace: &str, key: &str) -> Result<Vec<u8>, MockError>;
+    async fn write(&self, namespace: &str, key: &str, data: &[u8]) -> Result<(), MockError>;
+    async fn list(&self, namespace: &str) -> Result<Vec<String>, MockError>;
+    async fn create_namespace(&self, namespace: &str) -> Result<(), MockError>;
+    async fn delete_namespace(&self, namespace: &str) -> Result<(), MockError>;
+}
+
+/// Local-disk implementation, backed by the same `ServiceStore` used for
+/// service discovery. `std::fs` has no async API, so every call runs on a
+/// blocking thread.
+#[derive(Clone)]
+pub struct FsStore {
+    inner: ServiceStore,
+}
+
+impl FsStore {
+    pub fn new(inner: ServiceStore) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl Store for FsStore {
+    async fn read(&self, namespace: &str, key: &str) -> Result<Vec<u8>, MockError> {
+        let path = self.inner.service_path(namespace).join(key);
+        tokio::task::spawn_blocking(move || {
+            std::fs::read(&path).map_err(|e| MockError::IoError(format!("Failed to read {:?}: {}", path, e)))
+        })
+        .await
+        .map_err(|e| MockError::IoError(format!("Blocking read task failed: {}", e)))?
+    }
+
+    async fn write(&self, namespace: &str, key: &str, data: &[u8]) -> Result<(), MockError> {
+        let dir = self.inner.service_path(namespace);
+        let path = dir.join(key);
+        let data = data.to_vec();
+        tokio::task::spawn_blocking(move || {
+            std::fs::create_dir_all(&dir)
+                .map_err(|e| MockError::IoError(format!("Failed to create directory {:?}: {}", dir, e)))?;
+            std::fs::write(&path, data).map_err(|e| MockError::IoError(format!("Failed to write {:?}: {}", path, e)))
+        })
+        .await
+        .map_err(|e| MockError::IoError(format!("Blocking write task failed: {}", e)))?
+    }
+
+    async fn list(&self, namespace: &str) -> Result<Vec<String>, MockError> {
+        let dir = self.inner.service_path(namespace);
+        tokio::task::spawn_blocking(move || {
+            let entries = std::fs::read_dir(&dir)
+                .map_err(|e| MockError::IoError(format!("Failed to read directory {:?}: {}", dir, e)))?;
+
+            let mut keys = Vec::new();
+            for entry in entries {
+                let entry = entry.map_err(|e| MockError::IoError(format!("Failed to read directory entry: {}", e)))?;
+                if entry.path().is_file() {
+                    if let Some(name) = entry.file_name().to_str() {
+                        keys.push(name.to_string());
+                    }
+                }
+            }
+            keys.sort();
+            Ok(keys)
+        })
+        .await
+        .map_err(|e| MockError::IoError(format!("Blocking list task failed: {}", e)))?
+    }
+
+    async fn create_namespace(&self, namespace: &str) -> Result<(), MockError> {
+        let dir = self.inner.service_path(namespace);
+        tokio::task::spawn_blocking(move || {
+            if dir.exists() {
+                return Err(MockError::IoError(format!("Namespace already exists: {:?}", dir)));
+            }
+            std::fs::create_dir_all(&dir)
+                .map_err(|e| MockError::IoError(format!("Failed to create namespace {:?}: {}", dir, e)))
+        })
+        .await
+        .map_err(|e| MockError::IoError(format!("Blocking create_namespace task failed: {}", e)))?
+    }
+
+    async fn delete_namespace(&self, namespace: &str) -> Result<(), MockError> {
+        let dir = self.inner.service_path(namespace);
+        tokio::task::spawn_blocking(move || {
+            if !dir.exists() {
+                return Err(MockError::FileNotFound(format!("Namespace not found: {:?}", dir)));
+            }
+            std::fs::remove_dir_all(&dir)
+                .map_err(|e| MockError::IoError(format!("Failed to delete namespace {:?}: {}", dir, e)))
+        })
+        .await
+        .map_err(|e| MockError::IoError(format!("Blocking delete_namespace task failed: {}", e)))?
+    }
+}
+
+/// S3-compatible implementation built on the `object_store` crate, so the
+/// same trait works unchanged against AWS S3, MinIO, or any other
+/// S3-compatible endpoint. Credentials and region are resolved the same way
+/// `object_store`'s AWS builder always does, from the standard `AWS_*`
+/// environment variables.
+#[derive(Clone)]
+pub struct S3Store {
+    client: Arc<dyn ObjectStore>,
+}
+
+impl S3Store {
+    pub fn new(bucket: &str, endpoint: Option<&str>) -> Result<Self, MockError> {
+        let mut builder = AmazonS3Builder::from_env().with_bucket_name(bucket);
+        if let Some(endpoint) = endpoint {
+            // Custom endpoints (MinIO, etc.) are typically plain HTTP on a
+            // private network rather than TLS AWS.
+            builder = builder.with_endpoint(endpoint).with_allow_http(true);
+        }
+
+        let client = builder
+            .build()
+            .map_err(|e| MockError::IoError(format!("Failed to configure S3 store for bucket '{}': {}", bucket, e)))?;
+
+        Ok(Self { client: Arc::new(client) })
+    }
+
+    fn object_path(namespace: &str, key: &str) -> ObjectPath {
+        ObjectPath::from(format!("{}/{}", namespace, key))
+    }
+}
+
+#[async_trait]
+impl Store for S3Store {
+    async fn read(&self, namespace: &str, key: &str) -> Result<Vec<u8>, MockError> {
+        let path = Self::object_path(namespace, key);
+        let result = self.client.get(&path).await.map_err(|e| match e {
+            object_store::Error::NotFound { .. } => MockError::FileNotFound(format!("Object not found: {}", path)),
+            e => MockError::IoError(format!("Failed to read {}: {}", path, e)),
+        })?;
+
+        let bytes = result
+            .bytes()
+            .await
+            .map_err(|e| MockError::IoError(format!("Failed to read body of {}: {}", path, e)))?;
+        Ok(bytes.to_vec())
+    }
+
+    async fn write(&self, namespace: &str, key: &str, data: &[u8]) -> Result<(), MockError> {
+        let path = Self::object_path(namespace, key);
+        self.client
+            .put(&path, data.to_vec().into())
+            .await
+            .map(|_| ())
+            .map_err(|e| MockError::IoError(format!("Failed to write {}: {}", path, e)))
+    }
+
+    async fn list(&self, namespace: &str) -> Result<Vec<String>, MockError> {
+        let prefix = ObjectPath::from(format!("{}/", namespace));
+        let mut stream = self.client.list(Some(&prefix));
+
+        let mut keys = Vec::new();
+        while let Some(meta) = stream.next().await {
+            let meta = meta.map_err(|e| MockError::IoError(format!("Failed to list {}: {}", prefix, e)))?;
+            if let Some(key) = meta.location.as_ref().strip_prefix(&format!("{}/", namespace)) {
+                keys.push(key.to_string());
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn create_namespace(&self, namespace: &str) -> Result<(), MockError> {
+        // Object stores have no real directories, so namespace existence is
+        // implied entirely by whether any object shares its prefix. Check
+        // for one before writing, mirroring FsStore's own already-exists
+        // error, so creating a duplicate service fails the same way
+        // regardless of storage backend.
+        let prefix = ObjectPath::from(format!("{}/", namespace));
+        {
+            let mut stream = self.client.list(Some(&prefix));
+            if let Some(meta) = stream.next().await {
+                meta.map_err(|e| MockError::IoError(format!("Failed to list {}: {}", prefix, e)))?;
+                return Err(MockError::IoError(format!("Namespace already exists: {}", namespace)));
+            }
+        }
+
+        // Write a zero-byte marker so an explicitly created (but still
+        // empty) namespace is still distinguishable from one that was
+        // never created at all.
+        let marker = Self::object_path(namespace, ".keep");
+        self.client
+            .put(&marker, Vec::new().into())
+            .await
+            .map(|_| ())
+            .map_err(|e| MockError::IoError(format!("Failed to create namespace {}: {}", namespace, e)))
+    }
+
+    async fn delete_namespace(&self, namespace: &str) -> Result<(), MockError> {
+        let prefix = ObjectPath::from(format!("{}/", namespace));
+        let mut stream = self.client.list(Some(&prefix));
+
+        let mut found = false;
+        while let Some(meta) = stream.next().await {
+            let meta = meta.map_err(|e| MockError::IoError(format!("Failed to list {}: {}", prefix, e)))?;
+            found = true;
+            self.client
+                .delete(&meta.location)
+                .await
+                .map_err(|e| MockError::IoError(format!("Failed to delete {}: {}", meta.location, e)))?;
+        }
+
+        if !found {
+            return Err(MockError::FileNotFound(format!("Namespace not found: {}", namespace)));
+        }
+        Ok(())
+    }
+}
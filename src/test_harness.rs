@@ -0,0 +1,294 @@
+use rquickjs::{Context, Runtime};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::time::Instant;
+
+use crate::utils::{MockError, ServiceConfig, ServiceRegistry, ServiceType};
+
+/// One event in the stream produced while running a service's `*.test.js`
+/// files, serialized as a JSON object tagged by its `event` field (`plan`,
+/// `wait`, or `result`).
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TestEvent {
+    Plan { total: usize, filtered: usize },
+    Wait { name: String },
+    Result { name: String, duration_ms: u64, outcome: TestOutcome },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", content = "reason", rename_all = "lowercase")]
+pub enum TestOutcome {
+    Ok,
+    Ignored,
+    Failed(String),
+}
+
+/// Aggregate result of `run_service_tests`: the full event stream plus
+/// pass/fail/ignored counts so callers can decide the process exit code.
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct TestSummary {
+    pub events: Vec<TestEvent>,
+    pub passed: usize,
+    pub failed: usize,
+    pub ignored: usize,
+}
+
+impl TestSummary {
+    /// Nonzero when any test failed, for use as a process exit code.
+    pub fn exit_code(&self) -> i32 {
+        if self.failed > 0 { 1 } else { 0 }
+    }
+}
+
+/// Discover `*.test.js` files under every dynamic service in `registry` and
+/// run them against that service's template/transformer, collecting a
+/// structured event stream and summary. Services with no test files are
+/// skipped; non-dynamic services have nothing to test against and are
+/// skipped too.
+pub fn run_service_tests(registry: &ServiceRegistry) -> TestSummary {
+    let mut summary = TestSummary::default();
+
+    let test_files: Vec<(String, std::path::PathBuf)> = registry
+        .services
+        .values()
+        .filter(|service| matches!(service.service_type, ServiceType::Dynamic { .. }))
+        .flat_map(|service| discover_test_files(service).into_iter().map(move |f| (service.name.clone(), f)))
+        .collect();
+
+    let total = test_files.len();
+    summary.events.push(TestEvent::Plan { total, filtered: 0 });
+
+    for (service_name, test_file) in test_files {
+        let service = match registry.services.get(&service_name) {
+            Some(service) => service,
+            None => continue,
+        };
+
+        match run_test_file(service, &test_file) {
+            Ok(cases) => {
+                for (name, outcome, duration_ms) in cases {
+                    summary.events.push(TestEvent::Wait { name: name.clone() });
+                    match &outcome {
+                        TestOutcome::Ok => summary.passed += 1,
+                        TestOutcome::Ignored => summary.ignored += 1,
+                        TestOutcome::Failed(_) => summary.failed += 1,
+                    }
+                    summary.events.push(TestEvent::Result { name, duration_ms, outcome });
+                }
+            }
+            Err(e) => {
+                let name = format!("{}/{}", service_name, test_file.display());
+                summary.events.push(TestEvent::Wait { name: name.clone() });
+                summary.failed += 1;
+                summary.events.push(TestEvent::Result {
+                    name,
+                    duration_ms: 0,
+                    outcome: TestOutcome::Failed(e.to_string()),
+                });
+            }
+        }
+    }
+
+    summary
+}
+
+fn discover_test_files(service: &ServiceConfig) -> Vec<std::path::PathBuf> {
+    fs::read_dir(&service.path)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.ends_with(".test.js")))
+        .collect()
+}
+
+/// Run every case registered by a single `*.test.js` file against the
+/// service's template and transformer, returning `(name, outcome, duration_ms)`
+/// per case in registration order.
+fn run_test_file(
+    service: &ServiceConfig,
+    test_file: &std::path::Path,
+) -> Result<Vec<(String, TestOutcome, u64)>, MockError> {
+    let (template, transformer) = match &service.service_type {
+        ServiceType::Dynamic { template, transformer, .. } => (template, transformer),
+        _ => return Err(MockError::ParseError(format!("{} is not a dynamic service", service.name))),
+    };
+
+    let test_code = fs::read_to_string(test_file)
+        .map_err(|e| MockError::IoError(format!("Failed to read {}: {}", test_file.display(), e)))?;
+
+    let rt = Runtime::new().map_err(|e| MockError::ParseError(format!("Failed to create JS runtime: {}", e)))?;
+    let ctx = Context::full(&rt).map_err(|e| MockError::ParseError(format!("Failed to create JS context: {}", e)))?;
+
+    ctx.with(|ctx| -> Result<Vec<(String, TestOutcome, u64)>, MockError> {
+        let template_str = serde_json::to_string(template)
+            .map_err(|e| MockError::ParseError(format!("Failed to serialize template: {}", e)))?;
+
+        let harness = format!(
+            r#"
+            const __template = {template_str};
+
+            {transformer}
+
+            if (typeof transform !== 'function') {{
+                throw new Error('transform function not defined in transformer');
+            }}
+
+            const __cases = [];
+            function test(name, fn, opts) {{
+                __cases.push({{ name: name, fn: fn, ignore: !!(opts && opts.ignore) }});
+            }}
+
+            const assert = {{
+                deepEqual(actual, expected, message) {{
+                    if (JSON.stringify(actual) !== JSON.stringify(expected)) {{
+                        throw new Error(message || ('expected ' + JSON.stringify(expected) + ', got ' + JSON.stringify(actual)));
+                    }}
+                }},
+                ok(value, message) {{
+                    if (!value) {{
+                        throw new Error(message || 'assertion failed');
+                    }}
+                }},
+            }};
+
+            {test_code}
+
+            const __results = [];
+            for (const c of __cases) {{
+                if (c.ignore) {{
+                    __results.push({{ name: c.name, outcome: 'ignored' }});
+                    continue;
+                }}
+
+                const __start = Date.now();
+                try {{
+                    c.fn(function(params, context) {{
+                        const ctx = context || {{ timestamp: new Date().toISOString(), requestId: 'test' }};
+                        return transform(__template, params, ctx);
+                    }}, assert);
+                    __results.push({{ name: c.name, outcome: 'ok', duration_ms: Date.now() - __start }});
+                }} catch (e) {{
+                    __results.push({{
+                        name: c.name,
+                        outcome: 'failed',
+                        reason: String(e && e.message || e),
+                        duration_ms: Date.now() - __start,
+                    }});
+                }}
+            }}
+            JSON.stringify(__results);
+            "#,
+        );
+
+        let result: String = ctx
+            .eval(harness.as_bytes())
+            .map_err(|e| MockError::ParseError(format!("Test harness execution failed: {}", e)))?;
+
+        let raw_results: Vec<Value> = serde_json::from_str(&result)
+            .map_err(|e| MockError::ParseError(format!("Invalid test harness output: {}", e)))?;
+
+        Ok(raw_results
+            .into_iter()
+            .map(|entry| {
+                let name = entry.get("name").and_then(Value::as_str).unwrap_or("<unnamed>").to_string();
+                let duration_ms = entry.get("duration_ms").and_then(Value::as_u64).unwrap_or(0);
+                let outcome = match entry.get("outcome").and_then(Value::as_str) {
+                    Some("ignored") => TestOutcome::Ignored,
+                    Some("failed") => TestOutcome::Failed(
+                        entry.get("reason").and_then(Value::as_str).unwrap_or("unknown error").to_string(),
+                    ),
+                    _ => TestOutcome::Ok,
+                };
+                (name, outcome, duration_ms)
+            })
+            .collect())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::RouteConfig;
+    use std::collections::HashMap;
+
+    fn dynamic_service(temp_dir: &std::path::Path, transformer: &str, test_code: &str) -> ServiceConfig {
+        std::fs::write(temp_dir.join("echo.test.js"), test_code).unwrap();
+
+        ServiceConfig {
+            name: "echo".to_string(),
+            service_type: ServiceType::Dynamic {
+                template: serde_json::json!({}),
+                transformer: transformer.to_string(),
+                route_config: RouteConfig {
+                    pattern: "/echo".to_string(),
+                    method: "GET".to_string(),
+                    params: HashMap::new(),
+                    cache_ttl: None,
+                    description: None,
+                    errors: HashMap::new(),
+                },
+            },
+            path: temp_dir.to_path_buf(),
+            latency: HashMap::new(),
+            faults: HashMap::new(),
+            rules: HashMap::new(),
+            default_headers: HashMap::new(),
+            scope_prefix: None,
+        }
+    }
+
+    fn registry_with(service: ServiceConfig) -> ServiceRegistry {
+        let mut registry = ServiceRegistry::new();
+        registry.upsert_service(service.name.clone(), service);
+        registry
+    }
+
+    #[test]
+    fn discover_test_files_finds_only_dot_test_js_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("echo.test.js"), "").unwrap();
+        std::fs::write(temp_dir.path().join("transformer.js"), "").unwrap();
+
+        let service = dynamic_service(temp_dir.path(), "function transform(t,p,c){return p;}", "");
+        let files = discover_test_files(&service);
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].file_name().unwrap().to_str().unwrap(), "echo.test.js");
+    }
+
+    #[test]
+    fn run_service_tests_counts_passed_failed_and_ignored() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let test_code = r#"
+            test("passes", (call, assert) => assert.ok(true));
+            test("fails", (call, assert) => assert.ok(false, "boom"));
+            test("skipped", (call, assert) => assert.ok(true), { ignore: true });
+        "#;
+        let service = dynamic_service(temp_dir.path(), "function transform(t,p,c){return p;}", test_code);
+        let registry = registry_with(service);
+
+        let summary = run_service_tests(&registry);
+
+        assert_eq!(summary.passed, 1);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.ignored, 1);
+        assert_eq!(summary.exit_code(), 1);
+    }
+
+    #[test]
+    fn run_service_tests_all_pass_exits_zero() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let test_code = r#"test("passes", (call, assert) => assert.ok(true));"#;
+        let service = dynamic_service(temp_dir.path(), "function transform(t,p,c){return p;}", test_code);
+        let registry = registry_with(service);
+
+        let summary = run_service_tests(&registry);
+
+        assert_eq!(summary.passed, 1);
+        assert_eq!(summary.failed, 0);
+        assert_eq!(summary.exit_code(), 0);
+    }
+}
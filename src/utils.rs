@@ -12,6 +12,8 @@ use once_cell::sync::Lazy;
 use walkdir::WalkDir;
 
 use crate::handlers::ServiceInfo;
+use crate::jsonrpc::RpcMethodConfig;
+use crate::latency::LatencyConfig;
 
 const SERVICES_DIR: &str = "services";
 
@@ -22,6 +24,34 @@ static HANDLEBARS: Lazy<Handlebars> = Lazy::new(|| {
     hb
 });
 
+// Matches `${VAR}` and `${VAR:-default}` references inside a template, so
+// fixtures can pull in environment-specific values (e.g. a host name) without
+// baking them into the JSON/YAML itself.
+static ENV_VAR_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)(:-([^}]*))?\}").unwrap());
+
+/// Expand `${ENV_VAR}` / `${ENV_VAR:-default}` references against the
+/// process environment. Unset variables with no `:-default` expand to the
+/// empty string rather than failing the request.
+fn expand_env_vars(input: &str) -> String {
+    ENV_VAR_REGEX
+        .replace_all(input, |caps: &regex::Captures| {
+            let default = caps.get(3).map(|m| m.as_str()).unwrap_or("");
+            let value = std::env::var(&caps[1]).unwrap_or_else(|_| default.to_string());
+            json_escape_fragment(&value)
+        })
+        .to_string()
+}
+
+/// JSON-escape `value` for splicing into the middle of a JSON string literal
+/// that's already been serialized — `expand_env_vars` substitutes straight
+/// into the template's serialized text, so a value containing `"`, `\`, or a
+/// newline would otherwise corrupt the surrounding JSON.
+fn json_escape_fragment(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_else(|_| value.to_string());
+    quoted[1..quoted.len() - 1].to_string()
+}
+
 // Service configuration structures
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RouteConfig {
@@ -30,6 +60,12 @@ pub struct RouteConfig {
     pub params: HashMap<String, ParamConfig>,
     pub cache_ttl: Option<u64>,
     pub description: Option<String>,
+    /// Per-status error response templates, keyed by status code as a
+    /// string (e.g. `"404"`, `"422"`). Rendered through the same
+    /// Handlebars+transformer pipeline as a normal response when a request
+    /// against this route fails with a matching status.
+    #[serde(default)]
+    pub errors: HashMap<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +87,9 @@ pub enum ServiceType {
         transformer: String,
         route_config: RouteConfig,
     },
+    JsonRpc {
+        methods: HashMap<String, RpcMethodConfig>,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -58,12 +97,30 @@ pub struct ServiceConfig {
     pub name: String,
     pub service_type: ServiceType,
     pub path: PathBuf,
+    /// Per-HTTP-method latency behavior, loaded from `latency.json` if present.
+    pub latency: HashMap<String, LatencyConfig>,
+    /// Per-HTTP-method fault-injection behavior, loaded from `faults.json`
+    /// if present.
+    pub faults: HashMap<String, crate::latency::FaultConfig>,
+    /// Per-HTTP-method request-matching rules, loaded from `rules.json` if
+    /// present. Evaluated before the method's default response.
+    pub rules: HashMap<String, Vec<crate::matching::MatchRule>>,
+    /// Default response headers (e.g. `Server`, `Cache-Control`) applied to
+    /// every response from this service, loaded from `headers.json` if
+    /// present. A per-file override recorded in a method's `.meta.json`
+    /// sidecar takes precedence over these.
+    pub default_headers: HashMap<String, String>,
+    /// Base path prefix this service should be scoped under (e.g.
+    /// `/v1/users`), loaded from an optional `scope.json` file. When set,
+    /// `main` registers a dedicated `web::scope` for the service instead of
+    /// relying solely on the catch-all dynamic handler.
+    pub scope_prefix: Option<String>,
 }
 
 #[derive(Debug)]
 pub struct ServiceRegistry {
     pub services: HashMap<String, ServiceConfig>,
-    pub route_patterns: Vec<(Regex, String, String)>, // (regex, service_name, method)
+    pub route_patterns: Vec<(Regex, String, String, i32)>, // (regex, service_name, method, specificity)
 }
 
 impl ServiceRegistry {
@@ -73,13 +130,53 @@ impl ServiceRegistry {
             route_patterns: Vec::new(),
         }
     }
+
+    /// Insert or replace a single service's config, rebuilding its route
+    /// pattern entry (if dynamic) and keeping `route_patterns` sorted by
+    /// specificity. Used both by full discovery and by hot-reload to
+    /// atomically swap one service without re-running discovery entirely.
+    pub fn upsert_service(&mut self, service_name: String, config: ServiceConfig) {
+        self.route_patterns.retain(|(_, name, _, _)| name != &service_name);
+
+        if let ServiceType::Dynamic { route_config, .. } = &config.service_type {
+            if let Ok(regex) = convert_pattern_to_regex(&route_config.pattern) {
+                let specificity = pattern_specificity(&route_config.pattern);
+                self.route_patterns.push((
+                    regex,
+                    service_name.clone(),
+                    route_config.method.clone(),
+                    specificity,
+                ));
+            }
+        }
+
+        self.route_patterns.sort_by(|a, b| b.3.cmp(&a.3));
+        self.services.insert(service_name, config);
+    }
+
+    /// Remove a service and its route pattern entries, e.g. after its
+    /// directory is deleted from disk.
+    pub fn remove_service(&mut self, service_name: &str) {
+        self.services.remove(service_name);
+        self.route_patterns.retain(|(_, name, _, _)| name != service_name);
+    }
 }
 
+/// A `ServiceRegistry` shared between the serving handlers and the
+/// hot-reload watcher, so edits on disk can be applied to a live server
+/// without a restart.
+pub type SharedRegistry = std::sync::Arc<std::sync::RwLock<ServiceRegistry>>;
+
 #[derive(Debug)]
 pub enum MockError {
     FileNotFound(String),
     ParseError(String),
     IoError(String),
+    /// A request's parameters failed `validate_parameters` (missing a
+    /// required parameter or not matching its declared pattern/type).
+    /// Kept distinct from `ParseError` so it maps to its own status (422)
+    /// instead of a generic 400.
+    ValidationError(String),
 }
 
 impl fmt::Display for MockError {
@@ -88,150 +185,435 @@ impl fmt::Display for MockError {
             MockError::FileNotFound(msg) => write!(f, "File not found: {}", msg),
             MockError::ParseError(msg) => write!(f, "Parse error: {}", msg),
             MockError::IoError(msg) => write!(f, "IO error: {}", msg),
+            MockError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
         }
     }
 }
 
+/// Map a `MockError` to the HTTP status code it represents, for both
+/// generic error responses and selecting a route's per-status error
+/// template.
+pub fn error_status(error: &MockError) -> u16 {
+    match error {
+        MockError::FileNotFound(_) => 404,
+        MockError::ValidationError(_) => 422,
+        MockError::ParseError(_) => 400,
+        MockError::IoError(_) => 500,
+    }
+}
+
 impl std::error::Error for MockError {}
 
-/// Discover and load all services from the services directory
-pub fn discover_services() -> Result<ServiceRegistry, MockError> {
-    let mut registry = ServiceRegistry::new();
-    let services_path = Path::new(SERVICES_DIR);
-    
-    if !services_path.exists() {
-        fs::create_dir_all(services_path)
-            .map_err(|e| MockError::IoError(format!("Failed to create services directory: {}", e)))?;
-        return Ok(registry);
+/// Holds the filesystem root that service directories are resolved against,
+/// so discovery and file I/O can be exercised against an isolated temp
+/// directory instead of always assuming the real working directory.
+#[derive(Debug, Clone)]
+pub struct ServiceStore {
+    root: PathBuf,
+}
+
+impl ServiceStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
     }
 
-    for entry in WalkDir::new(services_path)
-        .min_depth(1)
-        .max_depth(2)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        if entry.file_type().is_dir() {
-            let service_name = entry.file_name().to_string_lossy().to_string();
-            let service_path = entry.path();
-            
-            log::debug!("Discovering service: {} at {:?}", service_name, service_path);
-            
-            match load_service_config(&service_name, service_path) {
-                Ok(config) => {
-                    // Register route patterns for dynamic services
-                    if let ServiceType::Dynamic { route_config, .. } = &config.service_type {
-                        if let Ok(regex) = convert_pattern_to_regex(&route_config.pattern) {
-                            registry.route_patterns.push((
-                                regex,
-                                service_name.clone(),
-                                route_config.method.clone(),
-                            ));
-                            log::info!("Registered dynamic route: {} {} -> {}", 
+    pub(crate) fn service_path(&self, service_name: &str) -> PathBuf {
+        self.root.join(service_name)
+    }
+
+    /// The root directory this store resolves service directories under.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// (Re-)load a single service's config by name, without touching the
+    /// rest of a registry. Used by hot-reload to refresh one service after
+    /// a filesystem change.
+    pub fn load_service(&self, service_name: &str) -> Result<ServiceConfig, MockError> {
+        Self::load_service_config(service_name, &self.service_path(service_name))
+    }
+
+    /// Discover and load all services under this store's root
+    pub fn discover_services(&self) -> Result<ServiceRegistry, MockError> {
+        let mut registry = ServiceRegistry::new();
+
+        if !self.root.exists() {
+            fs::create_dir_all(&self.root)
+                .map_err(|e| MockError::IoError(format!("Failed to create services directory: {}", e)))?;
+            return Ok(registry);
+        }
+
+        for entry in WalkDir::new(&self.root)
+            .min_depth(1)
+            .max_depth(2)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            if entry.file_type().is_dir() {
+                let service_name = entry.file_name().to_string_lossy().to_string();
+                let service_path = entry.path();
+
+                log::debug!("Discovering service: {} at {:?}", service_name, service_path);
+
+                match Self::load_service_config(&service_name, service_path) {
+                    Ok(config) => {
+                        if let ServiceType::Dynamic { route_config, .. } = &config.service_type {
+                            log::info!("Registered dynamic route: {} {} -> {}",
                                 route_config.method, route_config.pattern, service_name);
                         }
+
+                        registry.upsert_service(service_name.clone(), config);
+                        log::info!("Loaded service: {}", service_name);
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to load service {}: {}", service_name, e);
                     }
-                    
-                    registry.services.insert(service_name.clone(), config);
-                    log::info!("Loaded service: {}", service_name);
-                }
-                Err(e) => {
-                    log::warn!("Failed to load service {}: {}", service_name, e);
                 }
             }
         }
+
+        log::info!("Service discovery completed. Loaded {} services", registry.services.len());
+        Ok(registry)
     }
+}
+
+/// Default store rooted at `SERVICES_DIR`, backing the free-function API
+/// kept below for existing callers.
+pub(crate) static DEFAULT_STORE: Lazy<ServiceStore> = Lazy::new(|| ServiceStore::new(SERVICES_DIR));
 
-    log::info!("Service discovery completed. Loaded {} services", registry.services.len());
-    Ok(registry)
+/// Discover and load all services from the services directory
+pub fn discover_services() -> Result<ServiceRegistry, MockError> {
+    DEFAULT_STORE.discover_services()
 }
 
-/// Load configuration for a specific service
-fn load_service_config(service_name: &str, service_path: &Path) -> Result<ServiceConfig, MockError> {
-    // Check if it's a dynamic service (has routes.json)
-    let routes_file = service_path.join("routes.json");
-    let template_file = service_path.join("template.json");
-    let transformer_file = service_path.join("transformer.js");
-    
-    if routes_file.exists() && template_file.exists() && transformer_file.exists() {
-        // Dynamic service
-        log::debug!("Loading dynamic service: {}", service_name);
-        
-        let route_config: RouteConfig = serde_json::from_str(
-            &fs::read_to_string(&routes_file)
-                .map_err(|e| MockError::IoError(format!("Failed to read routes.json: {}", e)))?
-        ).map_err(|e| MockError::ParseError(format!("Invalid routes.json: {}", e)))?;
-        
-        let template: Value = serde_json::from_str(
-            &fs::read_to_string(&template_file)
-                .map_err(|e| MockError::IoError(format!("Failed to read template.json: {}", e)))?
-        ).map_err(|e| MockError::ParseError(format!("Invalid template.json: {}", e)))?;
-        
-        let transformer = fs::read_to_string(&transformer_file)
-            .map_err(|e| MockError::IoError(format!("Failed to read transformer.js: {}", e)))?;
-        
-        Ok(ServiceConfig {
-            name: service_name.to_string(),
-            service_type: ServiceType::Dynamic {
-                template,
-                transformer,
-                route_config,
-            },
-            path: service_path.to_path_buf(),
-        })
-    } else {
-        // Static service - look for method-specific JSON files
-        log::debug!("Loading static service: {}", service_name);
-        
-        // For now, we'll load the first JSON file we find as static content
-        // This maintains backward compatibility
-        let json_files: Vec<_> = fs::read_dir(service_path)
-            .map_err(|e| MockError::IoError(format!("Failed to read service directory: {}", e)))?
-            .filter_map(|entry| entry.ok())
-            .filter(|entry| {
-                entry.path().extension()
-                    .and_then(|ext| ext.to_str())
-                    .map(|ext| ext == "json")
-                    .unwrap_or(false)
+/// Return the path of the first candidate filename that exists under
+/// `service_path`, trying each in order (JSON before YAML).
+fn first_existing(service_path: &Path, candidates: &[&str]) -> Option<PathBuf> {
+    candidates
+        .iter()
+        .map(|name| service_path.join(name))
+        .find(|path| path.exists())
+}
+
+/// Parse a config file as JSON or YAML based on its extension, into any
+/// type that both formats can deserialize into.
+fn parse_config_file<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, MockError> {
+    let raw = fs::read_to_string(path)
+        .map_err(|e| MockError::IoError(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yaml") | Some("yml") => serde_yaml::from_str(&raw)
+            .map_err(|e| MockError::ParseError(format!("Invalid YAML in {}: {}", path.display(), e))),
+        _ => serde_json::from_str(&raw)
+            .map_err(|e| MockError::ParseError(format!("Invalid JSON in {}: {}", path.display(), e))),
+    }
+}
+
+/// Infer a `Content-Type` from an uploaded file's name by its extension,
+/// falling back to `application/octet-stream` when it has none or none
+/// recognized.
+pub fn mime_from_filename(filename: &str) -> &'static str {
+    match Path::new(filename).extension().and_then(|e| e.to_str()) {
+        Some(ext) => mime_for_extension(ext),
+        None => "application/octet-stream",
+    }
+}
+
+/// Infer a mock response's `Content-Type` from its stored file extension,
+/// so mocks can be plain text, XML, HTML, or arbitrary binary, not just
+/// JSON. Falls back to `application/octet-stream` for anything unknown.
+fn mime_for_extension(ext: &str) -> &'static str {
+    match ext.to_ascii_lowercase().as_str() {
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "html" | "htm" => "text/html",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "js" => "application/javascript",
+        "css" => "text/css",
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Public entry point for `extension_for_mime`, for callers outside this
+/// module (e.g. `handlers::upload_mock_file`) that need to pick a storage
+/// extension for an arbitrary uploaded `Content-Type`.
+pub fn extension_for_content_type(content_type: &str) -> &'static str {
+    extension_for_mime(content_type)
+}
+
+/// The inverse of `mime_for_extension`: the file extension a mock upload
+/// with the given `Content-Type` should be stored under, so a later read
+/// infers the same type back out of the filename.
+fn extension_for_mime(content_type: &str) -> &'static str {
+    match content_type.split(';').next().unwrap_or("").trim() {
+        "application/json" => "json",
+        "application/xml" | "text/xml" => "xml",
+        "text/html" => "html",
+        "text/plain" => "txt",
+        "text/csv" => "csv",
+        "application/javascript" => "js",
+        "text/css" => "css",
+        "application/pdf" => "pdf",
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        _ => "bin",
+    }
+}
+
+impl ServiceStore {
+    /// Load configuration for a specific service
+    fn load_service_config(service_name: &str, service_path: &Path) -> Result<ServiceConfig, MockError> {
+        // Check if it's a dynamic service (has routes.json/routes.yaml); either
+        // format is accepted so services can be authored in whichever is
+        // convenient.
+        let routes_path = first_existing(service_path, &["routes.json", "routes.yaml", "routes.yml"]);
+        let template_path = first_existing(service_path, &["template.json", "template.yaml", "template.yml"]);
+        let transformer_file = service_path.join("transformer.js");
+        let rpc_file = service_path.join("rpc.json");
+        let latency = load_latency_config(service_path)?;
+        let faults = load_fault_config(service_path)?;
+        let rules = load_rules_config(service_path)?;
+        let default_headers = load_default_headers(service_path)?;
+        let scope_prefix = load_scope_prefix(service_path)?;
+
+        if rpc_file.exists() {
+            // JSON-RPC 2.0 service
+            log::debug!("Loading JSON-RPC service: {}", service_name);
+
+            let rpc_config: crate::jsonrpc::RpcServiceConfig = serde_json::from_str(
+                &fs::read_to_string(&rpc_file)
+                    .map_err(|e| MockError::IoError(format!("Failed to read rpc.json: {}", e)))?
+            ).map_err(|e| MockError::ParseError(format!("Invalid rpc.json: {}", e)))?;
+
+            Ok(ServiceConfig {
+                name: service_name.to_string(),
+                service_type: ServiceType::JsonRpc { methods: rpc_config.methods },
+                path: service_path.to_path_buf(),
+                latency,
+                faults,
+                rules,
+                default_headers,
+                scope_prefix,
+            })
+        } else if let (Some(routes_path), Some(template_path)) = (&routes_path, &template_path) {
+            if !transformer_file.exists() {
+                return Err(MockError::FileNotFound(format!(
+                    "Dynamic service {} is missing transformer.js", service_name
+                )));
+            }
+
+            // Dynamic service
+            log::debug!("Loading dynamic service: {}", service_name);
+
+            let route_config: RouteConfig = parse_config_file(routes_path)?;
+            let template: Value = parse_config_file(template_path)?;
+
+            let transformer = fs::read_to_string(&transformer_file)
+                .map_err(|e| MockError::IoError(format!("Failed to read transformer.js: {}", e)))?;
+
+            Ok(ServiceConfig {
+                name: service_name.to_string(),
+                service_type: ServiceType::Dynamic {
+                    template,
+                    transformer,
+                    route_config,
+                },
+                path: service_path.to_path_buf(),
+                latency,
+                faults,
+                rules,
+                default_headers,
+                scope_prefix,
+            })
+        } else {
+            // Static service - look for method-specific JSON files
+            log::debug!("Loading static service: {}", service_name);
+
+            // For now, we'll load the first JSON file we find as static content
+            // This maintains backward compatibility
+            let json_files: Vec<_> = fs::read_dir(service_path)
+                .map_err(|e| MockError::IoError(format!("Failed to read service directory: {}", e)))?
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| {
+                    entry.path().extension()
+                        .and_then(|ext| ext.to_str())
+                        .map(|ext| ext == "json")
+                        .unwrap_or(false)
+                })
+                .collect();
+
+            if json_files.is_empty() {
+                return Err(MockError::FileNotFound(format!("No JSON files found in service directory: {:?}", service_path)));
+            }
+
+            // Load the first JSON file as default content
+            let first_file = &json_files[0];
+            let content: Value = serde_json::from_str(
+                &fs::read_to_string(first_file.path())
+                    .map_err(|e| MockError::IoError(format!("Failed to read JSON file: {}", e)))?
+            ).map_err(|e| MockError::ParseError(format!("Invalid JSON: {}", e)))?;
+
+            Ok(ServiceConfig {
+                name: service_name.to_string(),
+                service_type: ServiceType::Static { content },
+                path: service_path.to_path_buf(),
+                latency,
+                faults,
+                rules,
+                default_headers,
+                scope_prefix,
             })
-            .collect();
-        
-        if json_files.is_empty() {
-            return Err(MockError::FileNotFound(format!("No JSON files found in service directory: {:?}", service_path)));
         }
-        
-        // Load the first JSON file as default content
-        let first_file = &json_files[0];
-        let content: Value = serde_json::from_str(
-            &fs::read_to_string(first_file.path())
-                .map_err(|e| MockError::IoError(format!("Failed to read JSON file: {}", e)))?
-        ).map_err(|e| MockError::ParseError(format!("Invalid JSON: {}", e)))?;
-        
-        Ok(ServiceConfig {
-            name: service_name.to_string(),
-            service_type: ServiceType::Static { content },
-            path: service_path.to_path_buf(),
-        })
     }
 }
 
-/// Convert route pattern like "/plan-de-ruta/{ruta_id}/{fecha}" to regex
+/// Load the optional `latency.json` file from a service directory, mapping
+/// HTTP method to its `LatencyConfig`. Absent file means no configured
+/// latency for any method.
+fn load_latency_config(service_path: &Path) -> Result<HashMap<String, LatencyConfig>, MockError> {
+    let latency_file = service_path.join("latency.json");
+    if !latency_file.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let raw = fs::read_to_string(&latency_file)
+        .map_err(|e| MockError::IoError(format!("Failed to read latency.json: {}", e)))?;
+    let by_method: HashMap<String, LatencyConfig> = serde_json::from_str(&raw)
+        .map_err(|e| MockError::ParseError(format!("Invalid latency.json: {}", e)))?;
+
+    Ok(by_method
+        .into_iter()
+        .map(|(method, config)| (method.to_uppercase(), config))
+        .collect())
+}
+
+/// Load the optional `faults.json` file from a service directory, mapping
+/// HTTP method to its `FaultConfig`. Absent file means no configured fault
+/// injection for any method.
+fn load_fault_config(service_path: &Path) -> Result<HashMap<String, crate::latency::FaultConfig>, MockError> {
+    let faults_file = service_path.join("faults.json");
+    if !faults_file.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let raw = fs::read_to_string(&faults_file)
+        .map_err(|e| MockError::IoError(format!("Failed to read faults.json: {}", e)))?;
+    let by_method: HashMap<String, crate::latency::FaultConfig> = serde_json::from_str(&raw)
+        .map_err(|e| MockError::ParseError(format!("Invalid faults.json: {}", e)))?;
+
+    Ok(by_method
+        .into_iter()
+        .map(|(method, config)| (method.to_uppercase(), config))
+        .collect())
+}
+
+/// Load the optional `rules.json` file from a service directory, mapping
+/// HTTP method to its ordered list of request-matching rules. Absent file
+/// means no rules configured for any method, so every request falls
+/// straight through to the method's default response.
+fn load_rules_config(service_path: &Path) -> Result<HashMap<String, Vec<crate::matching::MatchRule>>, MockError> {
+    let rules_file = service_path.join("rules.json");
+    if !rules_file.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let raw = fs::read_to_string(&rules_file)
+        .map_err(|e| MockError::IoError(format!("Failed to read rules.json: {}", e)))?;
+    let by_method: HashMap<String, Vec<crate::matching::MatchRule>> = serde_json::from_str(&raw)
+        .map_err(|e| MockError::ParseError(format!("Invalid rules.json: {}", e)))?;
+
+    Ok(by_method
+        .into_iter()
+        .map(|(method, rules)| (method.to_uppercase(), rules))
+        .collect())
+}
+
+/// Convert a route pattern like "/plan-de-ruta/{ruta_id}/{fecha}" to a regex.
+///
+/// A segment can carry an inline type constraint, e.g. `{id:\d+}`, which
+/// uses the part after `:` as the capture group's body instead of the
+/// default `[^/]+`. A `{*rest}` segment is a catch-all that captures the
+/// remainder of the path, including slashes.
 fn convert_pattern_to_regex(pattern: &str) -> Result<Regex, MockError> {
-    let mut regex_pattern = pattern.to_string();
-    
-    // Replace {param} with named capture groups
     let param_regex = Regex::new(r"\{([^}]+)\}")
         .map_err(|e| MockError::ParseError(format!("Invalid parameter regex: {}", e)))?;
-    
-    regex_pattern = param_regex.replace_all(&regex_pattern, r"(?P<$1>[^/]+)").to_string();
-    
+
+    let substituted = param_regex.replace_all(pattern, |caps: &regex::Captures| {
+        let inner = &caps[1];
+        if let Some(name) = inner.strip_prefix('*') {
+            format!("(?P<{}>.+)", name)
+        } else if let Some((name, constraint)) = inner.split_once(':') {
+            format!("(?P<{}>{})", name, constraint)
+        } else {
+            format!("(?P<{}>[^/]+)", inner)
+        }
+    });
+
     // Escape forward slashes and add anchors
-    regex_pattern = format!("^{}$", regex_pattern.replace("/", r"\/"));
-    
+    let regex_pattern = format!("^{}$", substituted.replace('/', r"\/"));
+
     Regex::new(&regex_pattern)
         .map_err(|e| MockError::ParseError(format!("Failed to compile route regex: {}", e)))
 }
 
+/// Score a route pattern's specificity so the most specific route wins
+/// regardless of discovery order: static literal segments rank highest,
+/// constrained params next, unconstrained params lower, and catch-alls
+/// lowest.
+fn pattern_specificity(pattern: &str) -> i32 {
+    pattern
+        .trim_start_matches('/')
+        .split('/')
+        .map(|segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(inner) if inner.starts_with('*') => 0,
+            Some(inner) if inner.contains(':') => 2,
+            Some(_) => 1,
+            None => 3,
+        })
+        .sum()
+}
+
+/// Load the optional `headers.json` file from a service directory: a flat
+/// map of default response headers applied to every response from the
+/// service. Absent file means no service-level defaults.
+fn load_default_headers(service_path: &Path) -> Result<HashMap<String, String>, MockError> {
+    let headers_file = service_path.join("headers.json");
+    if !headers_file.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let raw = fs::read_to_string(&headers_file)
+        .map_err(|e| MockError::IoError(format!("Failed to read headers.json: {}", e)))?;
+
+    serde_json::from_str(&raw).map_err(|e| MockError::ParseError(format!("Invalid headers.json: {}", e)))
+}
+
+/// Load the optional `scope.json` file from a service directory: a single
+/// `{"prefix": "/v1/users"}` declaring the base path the service should be
+/// mounted under as its own `web::scope` instead of the catch-all handler.
+fn load_scope_prefix(service_path: &Path) -> Result<Option<String>, MockError> {
+    let scope_file = service_path.join("scope.json");
+    if !scope_file.exists() {
+        return Ok(None);
+    }
+
+    #[derive(Deserialize)]
+    struct ScopeFile {
+        prefix: String,
+    }
+
+    let raw = fs::read_to_string(&scope_file)
+        .map_err(|e| MockError::IoError(format!("Failed to read scope.json: {}", e)))?;
+    let parsed: ScopeFile = serde_json::from_str(&raw)
+        .map_err(|e| MockError::ParseError(format!("Invalid scope.json: {}", e)))?;
+
+    Ok(Some(parsed.prefix))
+}
+
 /// Process a dynamic service request with parameters
 pub fn process_dynamic_service(
     service_config: &ServiceConfig,
@@ -259,9 +641,32 @@ pub fn process_dynamic_service(
         ServiceType::Static { .. } => {
             Err(MockError::ParseError("Cannot process static service as dynamic".to_string()))
         }
+        ServiceType::JsonRpc { .. } => {
+            Err(MockError::ParseError("Cannot process JSON-RPC service as REST dynamic".to_string()))
+        }
     }
 }
 
+/// Resolve a dynamic service's per-status `errors` template (if the route
+/// declares one for the given error) into a rendered body, through the same
+/// Handlebars+transformer pipeline used for a successful response. Returns
+/// `None` when the service isn't dynamic, declares no `errors` map, or has
+/// no template for this error's status, so the caller can fall back to a
+/// plain string error.
+pub fn resolve_error_template(
+    service_config: &ServiceConfig,
+    error: &MockError,
+    params: &HashMap<String, String>,
+) -> Option<Value> {
+    let ServiceType::Dynamic { transformer, route_config, .. } = &service_config.service_type else {
+        return None;
+    };
+
+    let template = route_config.errors.get(&error_status(error).to_string())?;
+    let template_with_params = apply_template_substitution(template, params).ok()?;
+    execute_transformer(&template_with_params, transformer, params).ok()
+}
+
 /// Validate request parameters against route configuration
 fn validate_parameters(
     params: &HashMap<String, String>,
@@ -278,18 +683,18 @@ fn validate_parameters(
                         .map_err(|e| MockError::ParseError(format!("Invalid parameter regex: {}", e)))?;
                     
                     if !regex.is_match(value) {
-                        return Err(MockError::ParseError(format!(
+                        return Err(MockError::ValidationError(format!(
                             "Parameter '{}' value '{}' doesn't match pattern '{}'",
                             param_name, value, pattern
                         )));
                     }
                 }
-                
+
                 // Validate parameter type
                 match config.param_type.as_str() {
                     "date" => {
                         if chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_err() {
-                            return Err(MockError::ParseError(format!(
+                            return Err(MockError::ValidationError(format!(
                                 "Parameter '{}' must be a valid date in YYYY-MM-DD format",
                                 param_name
                             )));
@@ -297,7 +702,7 @@ fn validate_parameters(
                     }
                     "number" => {
                         if value.parse::<f64>().is_err() {
-                            return Err(MockError::ParseError(format!(
+                            return Err(MockError::ValidationError(format!(
                                 "Parameter '{}' must be a valid number",
                                 param_name
                             )));
@@ -313,7 +718,7 @@ fn validate_parameters(
             }
             None => {
                 if required {
-                    return Err(MockError::ParseError(format!(
+                    return Err(MockError::ValidationError(format!(
                         "Required parameter '{}' is missing",
                         param_name
                     )));
@@ -332,7 +737,8 @@ fn apply_template_substitution(
 ) -> Result<Value, MockError> {
     let template_str = serde_json::to_string(template)
         .map_err(|e| MockError::ParseError(format!("Failed to serialize template: {}", e)))?;
-    
+    let template_str = expand_env_vars(&template_str);
+
     let rendered = HANDLEBARS.render_template(&template_str, params)
         .map_err(|e| MockError::ParseError(format!("Template rendering failed: {}", e)))?;
     
@@ -394,13 +800,14 @@ fn execute_transformer(
     })
 }
 
-/// Match request path against dynamic route patterns
+/// Match request path against dynamic route patterns, which are sorted by
+/// specificity at discovery time, so the first match is the most specific.
 pub fn match_dynamic_route(
     registry: &ServiceRegistry,
     path: &str,
     method: &str,
 ) -> Option<(String, HashMap<String, String>)> {
-    for (regex, service_name, route_method) in &registry.route_patterns {
+    for (regex, service_name, route_method, _specificity) in &registry.route_patterns {
         if route_method.to_uppercase() == method.to_uppercase() {
             if let Some(captures) = regex.captures(path) {
                 let mut params = HashMap::new();
@@ -419,152 +826,540 @@ pub fn match_dynamic_route(
     None
 }
 
-/// Read a mock file for a given service and HTTP method
-pub fn read_mock_file(service_name: &str, method: &str) -> Result<Value, MockError> {
-    let filename = format!("{}-{}.json", service_name, method.to_uppercase());
-    let file_path = PathBuf::from(SERVICES_DIR)
-        .join(service_name)
-        .join(&filename);
+impl ServiceStore {
+    /// Read a mock file for a given service and HTTP method
+    pub fn read_mock_file(&self, service_name: &str, method: &str) -> Result<Value, MockError> {
+        let filename = format!("{}-{}.json", service_name, method.to_uppercase());
+        let file_path = self.service_path(service_name).join(&filename);
 
-    log::debug!("Looking for mock file: {:?}", file_path);
+        log::debug!("Looking for mock file: {:?}", file_path);
 
-    if !file_path.exists() {
-        return Err(MockError::FileNotFound(format!(
-            "Mock file not found: {} for service '{}' and method '{}'",
-            filename, service_name, method
-        )));
-    }
+        if !file_path.exists() {
+            return Err(MockError::FileNotFound(format!(
+                "Mock file not found: {} for service '{}' and method '{}'",
+                filename, service_name, method
+            )));
+        }
 
-    let content = fs::read_to_string(&file_path)
-        .map_err(|e| MockError::IoError(format!("Failed to read file {:?}: {}", file_path, e)))?;
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| MockError::IoError(format!("Failed to read file {:?}: {}", file_path, e)))?;
 
-    serde_json::from_str(&content)
-        .map_err(|e| MockError::ParseError(format!("Invalid JSON in file {:?}: {}", file_path, e)))
-}
+        serde_json::from_str(&content)
+            .map_err(|e| MockError::ParseError(format!("Invalid JSON in file {:?}: {}", file_path, e)))
+    }
 
-/// Get a list of all available services
-pub fn get_services_list() -> Result<Vec<ServiceInfo>, MockError> {
-    let services_path = Path::new(SERVICES_DIR);
-    
-    if !services_path.exists() {
-        fs::create_dir_all(services_path)
-            .map_err(|e| MockError::IoError(format!("Failed to create services directory: {}", e)))?;
-        return Ok(vec![]);
+    /// Find the mock file stored for a service+method under any extension
+    /// (e.g. `users-GET.json`, `users-GET.xml`, `users-GET.bin`), skipping
+    /// the `.meta.json` metadata sidecar that shares its prefix.
+    fn find_mock_file(&self, service_name: &str, method: &str) -> Option<PathBuf> {
+        let prefix = format!("{}-{}.", service_name, method.to_uppercase());
+        let entries = fs::read_dir(self.service_path(service_name)).ok()?;
+
+        entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .find(|path| {
+                path.is_file()
+                    && path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.starts_with(&prefix) && !name.ends_with(".meta.json"))
+            })
     }
 
-    let mut services = Vec::new();
+    /// Read the raw bytes of whatever mock file is stored for a
+    /// service+method, alongside the `Content-Type` it should be served
+    /// with: an explicit override recorded at upload time takes precedence
+    /// over the type inferred from the file's extension.
+    pub fn read_mock_body(&self, service_name: &str, method: &str) -> Result<(Vec<u8>, String), MockError> {
+        let file_path = self.find_mock_file(service_name, method).ok_or_else(|| {
+            MockError::FileNotFound(format!(
+                "Mock file not found for service '{}' and method '{}'",
+                service_name, method
+            ))
+        })?;
 
-    let entries = fs::read_dir(services_path)
-        .map_err(|e| MockError::IoError(format!("Failed to read services directory: {}", e)))?;
+        let data = fs::read(&file_path)
+            .map_err(|e| MockError::IoError(format!("Failed to read file {:?}: {}", file_path, e)))?;
 
-    for entry in entries {
-        let entry = entry
-            .map_err(|e| MockError::IoError(format!("Failed to read directory entry: {}", e)))?;
-        
-        let path = entry.path();
-        if path.is_dir() {
-            let service_name = path.file_name()
-                .and_then(|name| name.to_str())
-                .unwrap_or("")
-                .to_string();
-
-            let methods = get_service_methods(&service_name)?;
-            
-            services.push(ServiceInfo {
-                name: service_name,
-                methods,
+        let content_type = self
+            .read_response_meta(service_name, method)
+            .and_then(|(_, _, content_type)| content_type)
+            .unwrap_or_else(|| {
+                let ext = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
+                mime_for_extension(ext).to_string()
             });
+
+        Ok((data, content_type))
+    }
+
+    /// Save the raw bytes of a mock response for a service+method, storing
+    /// it under the file extension that matches `content_type` (or `.json`
+    /// when none is given, preserving today's default) and recording the
+    /// type explicitly so a later read doesn't have to guess it back from
+    /// an ambiguous extension like `.bin`.
+    pub fn save_mock_body(
+        &self,
+        service_name: &str,
+        method: &str,
+        data: &[u8],
+        content_type: Option<&str>,
+    ) -> Result<(), MockError> {
+        let service_path = self.service_path(service_name);
+        if !service_path.exists() {
+            fs::create_dir_all(&service_path)
+                .map_err(|e| MockError::IoError(format!("Failed to create service directory {:?}: {}", service_path, e)))?;
+        }
+
+        // Replace whatever file (under any extension) previously served
+        // this service+method, so uploading a new content type doesn't
+        // leave a stale fixture behind under the old one.
+        if let Some(existing) = self.find_mock_file(service_name, method) {
+            fs::remove_file(&existing)
+                .map_err(|e| MockError::IoError(format!("Failed to replace existing mock file {:?}: {}", existing, e)))?;
         }
+
+        let ext = content_type.map(extension_for_mime).unwrap_or("json");
+        let filename = format!("{}-{}.{}", service_name, method.to_uppercase(), ext);
+        let file_path = service_path.join(&filename);
+
+        fs::write(&file_path, data)
+            .map_err(|e| MockError::IoError(format!("Failed to write file {:?}: {}", file_path, e)))?;
+
+        if let Some(content_type) = content_type {
+            self.save_content_type_override(service_name, method, content_type)?;
+        }
+
+        log::info!("Saved mock file: {:?}", file_path);
+        Ok(())
     }
 
-    services.sort_by(|a, b| a.name.cmp(&b.name));
-    Ok(services)
-}
+    /// Record just the `Content-Type` override in a service+method's
+    /// metadata sidecar, preserving whatever status/headers it already
+    /// carries (e.g. from a prior proxy recording).
+    fn save_content_type_override(&self, service_name: &str, method: &str, content_type: &str) -> Result<(), MockError> {
+        let (status, headers, _) = self
+            .read_response_meta(service_name, method)
+            .unwrap_or((200, HashMap::new(), None));
+        self.save_response_meta(service_name, method, status, &headers, Some(content_type))
+    }
+
+    /// Get a list of all available services
+    pub fn get_services_list(&self) -> Result<Vec<ServiceInfo>, MockError> {
+        if !self.root.exists() {
+            fs::create_dir_all(&self.root)
+                .map_err(|e| MockError::IoError(format!("Failed to create services directory: {}", e)))?;
+            return Ok(vec![]);
+        }
 
-/// Get available HTTP methods for a service
-fn get_service_methods(service_name: &str) -> Result<Vec<String>, MockError> {
-    let service_path = PathBuf::from(SERVICES_DIR).join(service_name);
-    let mut methods = Vec::new();
+        let mut services = Vec::new();
 
-    let entries = fs::read_dir(&service_path)
-        .map_err(|e| MockError::IoError(format!("Failed to read service directory {:?}: {}", service_path, e)))?;
+        let entries = fs::read_dir(&self.root)
+            .map_err(|e| MockError::IoError(format!("Failed to read services directory: {}", e)))?;
 
-    for entry in entries {
-        let entry = entry
-            .map_err(|e| MockError::IoError(format!("Failed to read directory entry: {}", e)))?;
-        
-        let path = entry.path();
-        if path.is_file() {
-            if let Some(filename) = path.file_name().and_then(|name| name.to_str()) {
-                if filename.ends_with(".json") {
-                    // Extract method from filename pattern: service_name-METHOD.json
-                    let prefix = format!("{}-", service_name);
-                    if filename.starts_with(&prefix) && filename.len() > prefix.len() + 5 {
-                        let method = filename[prefix.len()..filename.len()-5].to_string();
-                        if ["GET", "POST", "PUT", "DELETE"].contains(&method.as_str()) {
-                            methods.push(method);
+        for entry in entries {
+            let entry = entry
+                .map_err(|e| MockError::IoError(format!("Failed to read directory entry: {}", e)))?;
+
+            let path = entry.path();
+            if path.is_dir() {
+                let service_name = path.file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or("")
+                    .to_string();
+
+                let methods = self.get_service_methods(&service_name)?;
+
+                services.push(ServiceInfo {
+                    name: service_name,
+                    methods,
+                });
+            }
+        }
+
+        services.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(services)
+    }
+
+    /// Get available HTTP methods for a service
+    fn get_service_methods(&self, service_name: &str) -> Result<Vec<String>, MockError> {
+        let service_path = self.service_path(service_name);
+        let mut methods = Vec::new();
+
+        let entries = fs::read_dir(&service_path)
+            .map_err(|e| MockError::IoError(format!("Failed to read service directory {:?}: {}", service_path, e)))?;
+
+        for entry in entries {
+            let entry = entry
+                .map_err(|e| MockError::IoError(format!("Failed to read directory entry: {}", e)))?;
+
+            let path = entry.path();
+            if path.is_file() {
+                if let Some(filename) = path.file_name().and_then(|name| name.to_str()) {
+                    // Mock fixtures may now be stored under any extension
+                    // (service_name-METHOD.<ext>), not just `.json`; only the
+                    // `.meta.json` sidecar is excluded from discovery.
+                    if !filename.ends_with(".meta.json") {
+                        let prefix = format!("{}-", service_name);
+                        if let Some(rest) = filename.strip_prefix(&prefix) {
+                            if let Some(dot) = rest.find('.') {
+                                let method = rest[..dot].to_string();
+                                if crate::routing::MOCKABLE_METHODS.contains(&method.as_str()) {
+                                    methods.push(method);
+                                }
+                            }
                         }
                     }
                 }
             }
         }
+
+        methods.sort();
+        methods.dedup();
+        Ok(methods)
     }
 
-    methods.sort();
-    Ok(methods)
-}
+    /// Create a new service directory
+    pub fn create_service_directory(&self, service_name: &str) -> Result<(), MockError> {
+        let service_path = self.service_path(service_name);
 
-/// Create a new service directory
-pub fn create_service_directory(service_name: &str) -> Result<(), MockError> {
-    let service_path = PathBuf::from(SERVICES_DIR).join(service_name);
-    
-    if service_path.exists() {
-        return Err(MockError::IoError(format!("Service directory already exists: {:?}", service_path)));
+        if service_path.exists() {
+            return Err(MockError::IoError(format!("Service directory already exists: {:?}", service_path)));
+        }
+
+        fs::create_dir_all(&service_path)
+            .map_err(|e| MockError::IoError(format!("Failed to create service directory {:?}: {}", service_path, e)))?;
+
+        log::info!("Created service directory: {:?}", service_path);
+        Ok(())
     }
 
-    fs::create_dir_all(&service_path)
-        .map_err(|e| MockError::IoError(format!("Failed to create service directory {:?}: {}", service_path, e)))?;
+    /// Save a JSON file for a service and method
+    pub fn save_json_file(&self, service_name: &str, method: &str, content: &Value) -> Result<(), MockError> {
+        let service_path = self.service_path(service_name);
 
-    log::info!("Created service directory: {:?}", service_path);
-    Ok(())
+        // Create service directory if it doesn't exist
+        if !service_path.exists() {
+            fs::create_dir_all(&service_path)
+                .map_err(|e| MockError::IoError(format!("Failed to create service directory {:?}: {}", service_path, e)))?;
+        }
+
+        let filename = format!("{}-{}.json", service_name, method.to_uppercase());
+        let file_path = service_path.join(&filename);
+
+        let json_string = serde_json::to_string_pretty(content)
+            .map_err(|e| MockError::ParseError(format!("Failed to serialize JSON: {}", e)))?;
+
+        fs::write(&file_path, json_string)
+            .map_err(|e| MockError::IoError(format!("Failed to write file {:?}: {}", file_path, e)))?;
+
+        log::info!("Saved mock file: {:?}", file_path);
+        Ok(())
+    }
+
+    /// Check whether a static mock file already exists for a service+method,
+    /// under any content type.
+    pub fn mock_file_exists(&self, service_name: &str, method: &str) -> bool {
+        self.find_mock_file(service_name, method).is_some()
+    }
+
+    /// Persist captured response metadata (status, headers, and optionally a
+    /// `Content-Type` override) alongside a recorded fixture, so a later
+    /// replay can reproduce them faithfully.
+    pub fn save_response_meta(
+        &self,
+        service_name: &str,
+        method: &str,
+        status: u16,
+        headers: &HashMap<String, String>,
+        content_type: Option<&str>,
+    ) -> Result<(), MockError> {
+        let service_path = self.service_path(service_name);
+        if !service_path.exists() {
+            fs::create_dir_all(&service_path)
+                .map_err(|e| MockError::IoError(format!("Failed to create service directory {:?}: {}", service_path, e)))?;
+        }
+
+        let filename = format!("{}-{}.meta.json", service_name, method.to_uppercase());
+        let meta = serde_json::json!({ "status": status, "headers": headers, "content_type": content_type });
+        let json_string = serde_json::to_string_pretty(&meta)
+            .map_err(|e| MockError::ParseError(format!("Failed to serialize response metadata: {}", e)))?;
+
+        fs::write(service_path.join(&filename), json_string)
+            .map_err(|e| MockError::IoError(format!("Failed to write response metadata {:?}: {}", service_path, e)))?;
+
+        Ok(())
+    }
+
+    /// Load previously recorded response metadata (status, headers, and an
+    /// optional `Content-Type` override) for a service+method, if any was
+    /// captured.
+    pub fn read_response_meta(&self, service_name: &str, method: &str) -> Option<(u16, HashMap<String, String>, Option<String>)> {
+        let filename = format!("{}-{}.meta.json", service_name, method.to_uppercase());
+        let path = self.service_path(service_name).join(filename);
+        let raw = fs::read_to_string(path).ok()?;
+        let value: Value = serde_json::from_str(&raw).ok()?;
+
+        let status = value.get("status")?.as_u64()? as u16;
+        let headers = value
+            .get("headers")?
+            .as_object()?
+            .iter()
+            .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+            .collect();
+        let content_type = value.get("content_type").and_then(|v| v.as_str()).map(|s| s.to_string());
+
+        Some((status, headers, content_type))
+    }
+
+    /// Delete a service directory and all its files
+    pub fn delete_service_directory(&self, service_name: &str) -> Result<(), MockError> {
+        let service_path = self.service_path(service_name);
+
+        if !service_path.exists() {
+            return Err(MockError::FileNotFound(format!("Service directory not found: {:?}", service_path)));
+        }
+
+        fs::remove_dir_all(&service_path)
+            .map_err(|e| MockError::IoError(format!("Failed to delete service directory {:?}: {}", service_path, e)))?;
+
+        log::info!("Deleted service directory: {:?}", service_path);
+        Ok(())
+    }
+}
+
+// Thin wrappers kept for backward compatibility: existing callers that don't
+// care about a custom root keep working unchanged against `DEFAULT_STORE`.
+
+/// Read a mock file for a given service and HTTP method
+pub fn read_mock_file(service_name: &str, method: &str) -> Result<Value, MockError> {
+    DEFAULT_STORE.read_mock_file(service_name, method)
+}
+
+/// Read the raw bytes and `Content-Type` of whatever mock file is stored for
+/// a service+method, regardless of its underlying content type.
+pub fn read_mock_body(service_name: &str, method: &str) -> Result<(Vec<u8>, String), MockError> {
+    DEFAULT_STORE.read_mock_body(service_name, method)
+}
+
+/// Save the raw bytes of a mock response for a service+method under the file
+/// extension matching `content_type` (or `.json` when none is given).
+pub fn save_mock_body(service_name: &str, method: &str, data: &[u8], content_type: Option<&str>) -> Result<(), MockError> {
+    DEFAULT_STORE.save_mock_body(service_name, method, data, content_type)
+}
+
+/// Get a list of all available services
+pub fn get_services_list() -> Result<Vec<ServiceInfo>, MockError> {
+    DEFAULT_STORE.get_services_list()
+}
+
+/// Create a new service directory
+pub fn create_service_directory(service_name: &str) -> Result<(), MockError> {
+    DEFAULT_STORE.create_service_directory(service_name)
 }
 
 /// Save a JSON file for a service and method
 pub fn save_json_file(service_name: &str, method: &str, content: &Value) -> Result<(), MockError> {
-    let service_path = PathBuf::from(SERVICES_DIR).join(service_name);
-    
-    // Create service directory if it doesn't exist
-    if !service_path.exists() {
-        fs::create_dir_all(&service_path)
-            .map_err(|e| MockError::IoError(format!("Failed to create service directory {:?}: {}", service_path, e)))?;
-    }
-
-    let filename = format!("{}-{}.json", service_name, method.to_uppercase());
-    let file_path = service_path.join(&filename);
+    DEFAULT_STORE.save_json_file(service_name, method, content)
+}
 
-    let json_string = serde_json::to_string_pretty(content)
-        .map_err(|e| MockError::ParseError(format!("Failed to serialize JSON: {}", e)))?;
+/// Check whether a static mock file already exists for a service+method,
+/// under any content type.
+pub fn mock_file_exists(service_name: &str, method: &str) -> bool {
+    DEFAULT_STORE.mock_file_exists(service_name, method)
+}
 
-    fs::write(&file_path, json_string)
-        .map_err(|e| MockError::IoError(format!("Failed to write file {:?}: {}", file_path, e)))?;
+/// Persist captured response metadata (status, headers, and optionally a
+/// `Content-Type` override) alongside a recorded fixture, so a later replay
+/// can reproduce them faithfully.
+pub fn save_response_meta(
+    service_name: &str,
+    method: &str,
+    status: u16,
+    headers: &HashMap<String, String>,
+    content_type: Option<&str>,
+) -> Result<(), MockError> {
+    DEFAULT_STORE.save_response_meta(service_name, method, status, headers, content_type)
+}
 
-    log::info!("Saved mock file: {:?}", file_path);
-    Ok(())
+/// Load previously recorded response metadata (status, headers, and an
+/// optional `Content-Type` override) for a service+method, if any was
+/// captured.
+pub fn read_response_meta(service_name: &str, method: &str) -> Option<(u16, HashMap<String, String>, Option<String>)> {
+    DEFAULT_STORE.read_response_meta(service_name, method)
 }
 
 /// Delete a service directory and all its files
 pub fn delete_service_directory(service_name: &str) -> Result<(), MockError> {
-    let service_path = PathBuf::from(SERVICES_DIR).join(service_name);
-    
-    if !service_path.exists() {
-        return Err(MockError::FileNotFound(format!("Service directory not found: {:?}", service_path)));
-    }
+    DEFAULT_STORE.delete_service_directory(service_name)
+}
 
-    fs::remove_dir_all(&service_path)
-        .map_err(|e| MockError::IoError(format!("Failed to delete service directory {:?}: {}", service_path, e)))?;
+// Store-backed counterparts of the mock-serving reads above: the handlers
+// that actually respond to a request go through these instead of
+// `DEFAULT_STORE`, so configuring `--s3-bucket` changes what gets served, not
+// just what an upload writes to. Service *discovery* (which services exist,
+// their routes/templates/latency/fault/rule config) is unaffected and still
+// always loads from local disk; only a service's stored mock body and its
+// `.meta.json` sidecar are store-backed.
 
-    log::info!("Deleted service directory: {:?}", service_path);
-    Ok(())
+/// Store-backed counterpart to `ServiceStore::read_mock_body`: find whatever
+/// key is stored for a service+method under any extension, read its bytes,
+/// and resolve its `Content-Type` from the `.meta.json` sidecar override (if
+/// any) or the key's extension.
+pub async fn read_mock_body_via_store(
+    store: &dyn crate::store::Store,
+    service_name: &str,
+    method: &str,
+) -> Result<(Vec<u8>, String), MockError> {
+    let keys = store.list(service_name).await?;
+    let prefix = format!("{}-{}.", service_name, method.to_uppercase());
+    let key = keys
+        .into_iter()
+        .find(|name| name.starts_with(&prefix) && !name.ends_with(".meta.json"))
+        .ok_or_else(|| {
+            MockError::FileNotFound(format!(
+                "Mock file not found for service '{}' and method '{}'",
+                service_name, method
+            ))
+        })?;
+
+    let data = store.read(service_name, &key).await?;
+
+    let content_type = read_response_meta_via_store(store, service_name, method)
+        .await
+        .and_then(|(_, _, content_type)| content_type)
+        .unwrap_or_else(|| mime_from_filename(&key).to_string());
+
+    Ok((data, content_type))
+}
+
+/// Store-backed counterpart to `ServiceStore::mock_file_exists`.
+pub async fn mock_file_exists_via_store(store: &dyn crate::store::Store, service_name: &str, method: &str) -> bool {
+    let prefix = format!("{}-{}.", service_name, method.to_uppercase());
+    store
+        .list(service_name)
+        .await
+        .map(|keys| keys.iter().any(|name| name.starts_with(&prefix) && !name.ends_with(".meta.json")))
+        .unwrap_or(false)
+}
+
+/// Store-backed counterpart to `ServiceStore::save_mock_body`: writes under
+/// the file extension matching `content_type` (or `.json` when none given),
+/// mirroring `handlers::upload_mock_file`'s own store-backed write.
+pub async fn save_mock_body_via_store(
+    store: &dyn crate::store::Store,
+    service_name: &str,
+    method: &str,
+    data: &[u8],
+    content_type: Option<&str>,
+) -> Result<(), MockError> {
+    let ext = content_type.map(extension_for_mime).unwrap_or("json");
+    let key = format!("{}-{}.{}", service_name, method.to_uppercase(), ext);
+    store.write(service_name, &key, data).await
+}
+
+/// Store-backed counterpart to `ServiceStore::save_response_meta`.
+pub async fn write_response_meta_via_store(
+    store: &dyn crate::store::Store,
+    service_name: &str,
+    method: &str,
+    status: u16,
+    headers: &HashMap<String, String>,
+    content_type: Option<&str>,
+) -> Result<(), MockError> {
+    let filename = format!("{}-{}.meta.json", service_name, method.to_uppercase());
+    let meta = serde_json::json!({ "status": status, "headers": headers, "content_type": content_type });
+    let json_bytes = serde_json::to_vec_pretty(&meta)
+        .map_err(|e| MockError::ParseError(format!("Failed to serialize response metadata: {}", e)))?;
+
+    store.write(service_name, &filename, &json_bytes).await
+}
+
+/// Store-backed counterpart to `ServiceStore::read_response_meta`.
+pub async fn read_response_meta_via_store(
+    store: &dyn crate::store::Store,
+    service_name: &str,
+    method: &str,
+) -> Option<(u16, HashMap<String, String>, Option<String>)> {
+    let filename = format!("{}-{}.meta.json", service_name, method.to_uppercase());
+    let raw = store.read(service_name, &filename).await.ok()?;
+    let value: Value = serde_json::from_slice(&raw).ok()?;
+
+    let status = value.get("status")?.as_u64()? as u16;
+    let headers = value
+        .get("headers")?
+        .as_object()?
+        .iter()
+        .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+        .collect();
+    let content_type = value.get("content_type").and_then(|v| v.as_str()).map(|s| s.to_string());
+
+    Some((status, headers, content_type))
+}
+
+/// Store-backed counterpart to `ServiceStore::get_service_methods`, used by
+/// `list_services` so a service's listed methods reflect whatever mock
+/// bodies actually live in the configured store rather than local disk.
+pub async fn get_service_methods_via_store(store: &dyn crate::store::Store, service_name: &str) -> Result<Vec<String>, MockError> {
+    let keys = store.list(service_name).await?;
+    let prefix = format!("{}-", service_name);
+
+    let mut methods: Vec<String> = keys
+        .into_iter()
+        .filter(|name| !name.ends_with(".meta.json"))
+        .filter_map(|name| {
+            let rest = name.strip_prefix(&prefix)?;
+            let dot = rest.find('.')?;
+            let method = rest[..dot].to_string();
+            crate::routing::MOCKABLE_METHODS.contains(&method.as_str()).then_some(method)
+        })
+        .collect();
+
+    methods.sort();
+    methods.dedup();
+    Ok(methods)
+}
+
+/// Load a PEM certificate chain for TLS termination.
+pub fn load_cert(path: &str) -> Result<Vec<rustls::pki_types::CertificateDer<'static>>, MockError> {
+    let file = fs::File::open(path)
+        .map_err(|e| MockError::IoError(format!("Failed to open TLS cert {}: {}", path, e)))?;
+    let mut reader = std::io::BufReader::new(file);
+
+    rustls_pemfile::certs(&mut reader)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| MockError::ParseError(format!("Failed to parse TLS cert {}: {}", path, e)))
+}
+
+/// Load a PEM private key for TLS termination.
+pub fn load_private_key(path: &str) -> Result<rustls::pki_types::PrivateKeyDer<'static>, MockError> {
+    let file = fs::File::open(path)
+        .map_err(|e| MockError::IoError(format!("Failed to open TLS key {}: {}", path, e)))?;
+    let mut reader = std::io::BufReader::new(file);
+
+    rustls_pemfile::private_key(&mut reader)
+        .map_err(|e| MockError::ParseError(format!("Failed to parse TLS key {}: {}", path, e)))?
+        .ok_or_else(|| MockError::ParseError(format!("No private key found in {}", path)))
+}
+
+/// Load a PEM CA bundle used to verify client certificates for mutual TLS.
+pub fn load_client_ca(path: &str) -> Result<rustls::RootCertStore, MockError> {
+    let file = fs::File::open(path)
+        .map_err(|e| MockError::IoError(format!("Failed to open client CA {}: {}", path, e)))?;
+    let mut reader = std::io::BufReader::new(file);
+
+    let certs = rustls_pemfile::certs(&mut reader)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| MockError::ParseError(format!("Failed to parse client CA {}: {}", path, e)))?;
+
+    let mut store = rustls::RootCertStore::empty();
+    for cert in certs {
+        store
+            .add(cert)
+            .map_err(|e| MockError::ParseError(format!("Invalid client CA certificate: {}", e)))?;
+    }
+
+    Ok(store)
 }
 
 /// Validate service name (alphanumeric and underscores only)
@@ -592,4 +1387,118 @@ mod tests {
         assert!(!validate_service_name("invalid-name"));
         assert!(!validate_service_name("invalid name"));
     }
+
+    #[test]
+    fn test_service_store_discovers_against_custom_root() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let service_dir = temp_dir.path().join("greeting");
+        fs::create_dir_all(&service_dir).unwrap();
+        fs::write(service_dir.join("greeting-GET.json"), r#"{"message": "hi"}"#).unwrap();
+
+        let store = ServiceStore::new(temp_dir.path());
+        let registry = store.discover_services().unwrap();
+
+        assert!(registry.services.contains_key("greeting"));
+        assert!(matches!(
+            registry.services["greeting"].service_type,
+            ServiceType::Static { .. }
+        ));
+    }
+
+    #[test]
+    fn test_service_store_get_service_methods() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let service_dir = temp_dir.path().join("users");
+        fs::create_dir_all(&service_dir).unwrap();
+        fs::write(service_dir.join("users-GET.json"), "{}").unwrap();
+        fs::write(service_dir.join("users-POST.json"), "{}").unwrap();
+
+        let store = ServiceStore::new(temp_dir.path());
+        let methods = store.get_service_methods("users").unwrap();
+
+        assert_eq!(methods, vec!["GET".to_string(), "POST".to_string()]);
+    }
+
+    #[test]
+    fn test_convert_pattern_to_regex_matches_typed_and_untyped_segments() {
+        let regex = convert_pattern_to_regex("/plan-de-ruta/{ruta_id:\\d+}/{fecha}").unwrap();
+
+        let caps = regex.captures("/plan-de-ruta/42/2026-07-26").unwrap();
+        assert_eq!(&caps["ruta_id"], "42");
+        assert_eq!(&caps["fecha"], "2026-07-26");
+
+        assert!(regex.captures("/plan-de-ruta/not-a-number/2026-07-26").is_none());
+    }
+
+    #[test]
+    fn test_convert_pattern_to_regex_catch_all_captures_remaining_slashes() {
+        let regex = convert_pattern_to_regex("/files/{*rest}").unwrap();
+        let caps = regex.captures("/files/a/b/c.txt").unwrap();
+        assert_eq!(&caps["rest"], "a/b/c.txt");
+    }
+
+    #[test]
+    fn test_service_store_save_and_read_mock_body_roundtrip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = ServiceStore::new(temp_dir.path());
+
+        store.save_mock_body("widgets", "GET", b"<xml>hi</xml>", Some("application/xml")).unwrap();
+        let (data, content_type) = store.read_mock_body("widgets", "GET").unwrap();
+
+        assert_eq!(data, b"<xml>hi</xml>");
+        assert_eq!(content_type, "application/xml");
+        assert!(store.mock_file_exists("widgets", "GET"));
+    }
+
+    #[test]
+    fn test_service_store_save_mock_body_replaces_prior_content_type() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = ServiceStore::new(temp_dir.path());
+
+        store.save_mock_body("widgets", "GET", b"{}", Some("application/json")).unwrap();
+        store.save_mock_body("widgets", "GET", b"plain text", Some("text/plain")).unwrap();
+
+        let (data, content_type) = store.read_mock_body("widgets", "GET").unwrap();
+        assert_eq!(data, b"plain text");
+        assert_eq!(content_type, "text/plain");
+    }
+
+    #[test]
+    fn test_service_store_response_meta_roundtrip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = ServiceStore::new(temp_dir.path());
+
+        let mut headers = HashMap::new();
+        headers.insert("X-Custom".to_string(), "yes".to_string());
+        store.save_response_meta("widgets", "GET", 201, &headers, Some("application/json")).unwrap();
+
+        let (status, read_headers, content_type) = store.read_response_meta("widgets", "GET").unwrap();
+        assert_eq!(status, 201);
+        assert_eq!(read_headers.get("X-Custom"), Some(&"yes".to_string()));
+        assert_eq!(content_type, Some("application/json".to_string()));
+    }
+
+    #[test]
+    fn test_service_store_create_and_delete_service_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = ServiceStore::new(temp_dir.path());
+
+        store.create_service_directory("widgets").unwrap();
+        assert!(store.create_service_directory("widgets").is_err());
+
+        store.delete_service_directory("widgets").unwrap();
+        assert!(matches!(store.delete_service_directory("widgets"), Err(MockError::FileNotFound(_))));
+    }
+
+    #[test]
+    fn test_pattern_specificity_ranks_static_over_typed_over_wildcard_over_catch_all() {
+        let static_score = pattern_specificity("/users/active");
+        let typed_score = pattern_specificity("/users/{id:\\d+}");
+        let wildcard_score = pattern_specificity("/users/{id}");
+        let catch_all_score = pattern_specificity("/users/{*rest}");
+
+        assert!(static_score > typed_score);
+        assert!(typed_score > wildcard_score);
+        assert!(wildcard_score > catch_all_score);
+    }
 }
\ No newline at end of file
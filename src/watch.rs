@@ -0,0 +1,150 @@
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::mpsc::channel;
+
+use crate::utils::{ServiceStore, SharedRegistry, DEFAULT_STORE};
+
+/// Watch the default services root and keep `registry` in sync with it:
+/// on any create/modify/remove event under a service's directory, reload
+/// just that service and atomically swap it into the registry, so a
+/// running server picks up edits without a restart.
+///
+/// Returns the watcher so the caller can keep it alive for as long as
+/// hot-reload should stay active; dropping it stops the watch.
+pub fn watch_services(registry: SharedRegistry) -> notify::Result<RecommendedWatcher> {
+    let root = DEFAULT_STORE.root().to_path_buf();
+    let (tx, rx) = channel::<Event>();
+
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+        Ok(event) => {
+            let _ = tx.send(event);
+        }
+        Err(e) => log::warn!("Filesystem watch error: {}", e),
+    })?;
+    watcher.watch(&root, RecursiveMode::Recursive)?;
+
+    log::info!("Watching {:?} for service changes", root);
+
+    std::thread::spawn(move || {
+        for event in rx {
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+                continue;
+            }
+
+            for changed_path in &event.paths {
+                if let Some(service_name) = service_name_from_path(&root, changed_path) {
+                    reload_service(&DEFAULT_STORE, &registry, &root, &service_name);
+                }
+            }
+        }
+    });
+
+    Ok(watcher)
+}
+
+/// Extract the top-level service directory name a changed path belongs to,
+/// e.g. `services/users/template.json` -> `Some("users")`.
+fn service_name_from_path(root: &Path, changed_path: &Path) -> Option<String> {
+    changed_path
+        .strip_prefix(root)
+        .ok()?
+        .components()
+        .next()?
+        .as_os_str()
+        .to_str()
+        .map(|s| s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, RwLock};
+
+    #[test]
+    fn service_name_from_path_extracts_top_level_dir() {
+        let root = Path::new("/services");
+        assert_eq!(
+            service_name_from_path(root, Path::new("/services/users/template.json")),
+            Some("users".to_string())
+        );
+    }
+
+    #[test]
+    fn service_name_from_path_none_outside_root() {
+        let root = Path::new("/services");
+        assert_eq!(service_name_from_path(root, Path::new("/elsewhere/users/template.json")), None);
+    }
+
+    #[test]
+    fn service_name_from_path_none_for_root_itself() {
+        let root = Path::new("/services");
+        assert_eq!(service_name_from_path(root, root), None);
+    }
+
+    #[test]
+    fn reload_service_upserts_when_still_on_disk() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = ServiceStore::new(temp_dir.path());
+        let service_dir = temp_dir.path().join("widgets");
+        std::fs::create_dir_all(&service_dir).unwrap();
+        std::fs::write(service_dir.join("widgets-GET.json"), r#"{"ok": true}"#).unwrap();
+
+        let registry: SharedRegistry = Arc::new(RwLock::new(crate::utils::ServiceRegistry::new()));
+        reload_service(&store, &registry, temp_dir.path(), "widgets");
+
+        assert!(registry.read().unwrap().services.contains_key("widgets"));
+    }
+
+    #[test]
+    fn reload_service_removes_when_directory_gone() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = ServiceStore::new(temp_dir.path());
+
+        let registry: SharedRegistry = Arc::new(RwLock::new(crate::utils::ServiceRegistry::new()));
+        registry.write().unwrap().upsert_service(
+            "widgets".to_string(),
+            crate::utils::ServiceConfig {
+                name: "widgets".to_string(),
+                service_type: crate::utils::ServiceType::Static { content: serde_json::json!({}) },
+                path: temp_dir.path().join("widgets"),
+                latency: Default::default(),
+                faults: Default::default(),
+                rules: Default::default(),
+                default_headers: Default::default(),
+                scope_prefix: None,
+            },
+        );
+
+        // No "widgets" directory was ever created on disk, so reloading
+        // should drop the now-stale entry instead of trying (and failing)
+        // to reload it.
+        reload_service(&store, &registry, temp_dir.path(), "widgets");
+
+        assert!(!registry.read().unwrap().services.contains_key("widgets"));
+    }
+}
+
+fn reload_service(store: &ServiceStore, registry: &SharedRegistry, root: &Path, service_name: &str) {
+    let service_path = root.join(service_name);
+
+    if !service_path.exists() {
+        let mut registry = registry.write().unwrap();
+        registry.remove_service(service_name);
+        log::info!("Service '{}' removed after filesystem change", service_name);
+        return;
+    }
+
+    match store.load_service(service_name) {
+        Ok(config) => {
+            let mut registry = registry.write().unwrap();
+            registry.upsert_service(service_name.to_string(), config);
+            log::info!("Reloaded service '{}' after filesystem change", service_name);
+        }
+        Err(e) => {
+            // Keep serving whatever was previously loaded rather than
+            // dropping the service on a transient or invalid edit (e.g. a
+            // half-written file from an editor's save).
+            log::warn!("Failed to reload service '{}': {}; keeping previous version", service_name, e);
+        }
+    }
+}